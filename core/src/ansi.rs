@@ -0,0 +1,184 @@
+use ratatui::buffer::{Buffer, Cell, CellDiffOption};
+use ratatui::layout::Position;
+use ratatui::style::{Color, Modifier};
+use std::fmt::Write;
+use tachyonfx::Duration;
+
+/// The buffer as text with ANSI escape codes, one line per row.
+pub fn ansi(buf: &Buffer) -> String {
+    let area = buf.area;
+
+    let mut ansi = String::new();
+    for y in area.top()..area.bottom() {
+        let mut sgr = SgrWriter::default();
+        for x in area.left()..area.right() {
+            let cell = &buf[(x, y)];
+            if cell.diff_option != CellDiffOption::Skip {
+                sgr.write_cell(&mut ansi, cell);
+            }
+        }
+        ansi.push_str("\x1b[0m\n");
+    }
+
+    ansi
+}
+
+/// An [asciinema v2](https://docs.asciinema.org/manual/asciicast/v2/) recording
+/// of the frames, `frame_duration` apart. After the first frame, only the
+/// cells which changed are written.
+pub fn asciicast(frames: &[Buffer], frame_duration: Duration) -> String {
+    let size = frames.first()
+        .map(|buf| buf.area.as_size())
+        .unwrap_or_default();
+
+    let mut cast = String::new();
+    let _ = writeln!(cast, r#"{{"version": 2, "width": {}, "height": {}}}"#, size.width, size.height);
+
+    // the terminal starts out cleared, as an empty buffer
+    let mut previous = Buffer::empty(frames.first().map(|buf| buf.area).unwrap_or_default());
+    let mut output = "\x1b[?25l\x1b[2J".to_string();
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut sgr = SgrWriter::default();
+        let mut cursor: Option<Position> = None;
+        for (x, y, cell) in previous.diff(frame) {
+            if cursor != Some(Position::new(x, y)) {
+                let _ = write!(output, "\x1b[{};{}H", y + 1, x + 1);
+            }
+            sgr.write_cell(&mut output, cell);
+            cursor = Some(Position::new(x + 1, y));
+        }
+
+        if !output.is_empty() {
+            output.push_str("\x1b[0m");
+            let seconds = (i as u32 * frame_duration.as_millis()) as f64 / 1000.0;
+            let _ = writeln!(cast, "[{seconds:.3}, \"o\", {}]", json_string(&output));
+            output.clear();
+        }
+
+        previous = frame.clone();
+    }
+
+    cast
+}
+
+/// Writes cells, switching the SGR attributes only when the style changes.
+#[derive(Default)]
+struct SgrWriter {
+    current: Option<(Color, Color, Modifier)>,
+}
+
+impl SgrWriter {
+    fn write_cell(&mut self, out: &mut String, cell: &Cell) {
+        let style = (cell.fg, cell.bg, cell.modifier);
+        if self.current != Some(style) {
+            out.push_str(&sgr(style));
+            self.current = Some(style);
+        }
+
+        out.push_str(cell.symbol());
+    }
+}
+
+/// The escape sequence resetting the attributes and then setting `fg`, `bg`
+/// and `modifier`.
+fn sgr((fg, bg, modifier): (Color, Color, Modifier)) -> String {
+    let mut sgr = "\x1b[0".to_string();
+
+    [
+        (Modifier::BOLD,        1),
+        (Modifier::DIM,         2),
+        (Modifier::ITALIC,      3),
+        (Modifier::UNDERLINED,  4),
+        (Modifier::SLOW_BLINK,  5),
+        (Modifier::RAPID_BLINK, 6),
+        (Modifier::REVERSED,    7),
+        (Modifier::HIDDEN,      8),
+        (Modifier::CROSSED_OUT, 9),
+    ].into_iter()
+        .filter(|(m, _)| modifier.contains(*m))
+        .for_each(|(_, code)| { let _ = write!(sgr, ";{code}"); });
+
+    write_color(&mut sgr, fg, 30);
+    write_color(&mut sgr, bg, 40);
+
+    sgr.push('m');
+    sgr
+}
+
+/// Writes the colour's SGR parameters; `base` is 30 for the foreground and
+/// 40 for the background.
+fn write_color(sgr: &mut String, color: Color, base: u8) {
+    let _ = match color {
+        Color::Reset           => Ok(()),
+        Color::Black           => write!(sgr, ";{}", base),
+        Color::Red             => write!(sgr, ";{}", base + 1),
+        Color::Green           => write!(sgr, ";{}", base + 2),
+        Color::Yellow          => write!(sgr, ";{}", base + 3),
+        Color::Blue            => write!(sgr, ";{}", base + 4),
+        Color::Magenta         => write!(sgr, ";{}", base + 5),
+        Color::Cyan            => write!(sgr, ";{}", base + 6),
+        Color::Gray            => write!(sgr, ";{}", base + 7),
+        Color::DarkGray        => write!(sgr, ";{}", base + 60),
+        Color::LightRed        => write!(sgr, ";{}", base + 61),
+        Color::LightGreen      => write!(sgr, ";{}", base + 62),
+        Color::LightYellow     => write!(sgr, ";{}", base + 63),
+        Color::LightBlue       => write!(sgr, ";{}", base + 64),
+        Color::LightMagenta    => write!(sgr, ";{}", base + 65),
+        Color::LightCyan       => write!(sgr, ";{}", base + 66),
+        Color::White           => write!(sgr, ";{}", base + 67),
+        Color::Indexed(i)      => write!(sgr, ";{};5;{i}", base + 8),
+        Color::Rgb(r, g, b)    => write!(sgr, ";{};2;{r};{g};{b}", base + 8),
+    };
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"'                  => json.push_str("\\\""),
+            '\\'                 => json.push_str("\\\\"),
+            '\n'                 => json.push_str("\\n"),
+            c if c < ' '         => { let _ = write!(json, "\\u{:04x}", c as u32); },
+            c                    => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    fn buffer(text: &str) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buf.set_string(0, 0, text, Style::new().fg(Color::Rgb(1, 2, 3)).bg(Color::Indexed(0)));
+        buf.set_string(0, 1, "b", Style::new().fg(Color::LightRed).bold());
+        buf
+    }
+
+    #[test]
+    fn test_ansi() {
+        assert_eq!(ansi(&buffer("ab")), [
+            "\x1b[0;38;2;1;2;3;48;5;0mab\x1b[0m  \x1b[0m",
+            "\x1b[0;1;91mb\x1b[0m   \x1b[0m",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_asciicast() {
+        let frames = [buffer("ab"), buffer("ab"), buffer("aa")];
+        let cast = asciicast(&frames, Duration::from_millis(100));
+
+        assert_eq!(cast.lines().collect::<Vec<_>>(), vec![
+            r#"{"version": 2, "width": 4, "height": 2}"#,
+            r#"[0.000, "o", "\u001b[?25l\u001b[2J\u001b[1;1H\u001b[0;38;2;1;2;3;48;5;0mab\u001b[2;1H\u001b[0;1;91mb\u001b[0m"]"#,
+            r#"[0.200, "o", "\u001b[1;2H\u001b[0;38;2;1;2;3;48;5;0ma\u001b[0m"]"#,
+        ]);
+    }
+}
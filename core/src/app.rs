@@ -2,28 +2,33 @@ use crate::dispatcher::Dispatcher;
 use crate::exabind_event::ExabindEvent;
 use crate::fx::effect::{outline_selected_category_key_caps, starting_up, UniqueEffectId};
 use crate::fx::effect;
+use crate::fx::overrides::{EffectEvent, EffectOverrides};
+use crate::fx::policy::EffectPolicy;
 use crate::input::InputProcessor;
 use crate::{KeyMap, Shortcut};
 use crate::stateful_widgets::StatefulWidgets;
+use crate::styling::{theme, ExabindTheme};
 use crate::ui_state::UiState;
 use crate::widget::{AnsiKeyboardTklLayout, KeyCap, KeyboardLayout};
 use crate::key_event::{KeyCode, ModifierKeyCode};
 use crate::key_event::ModifierKeyCode::{LeftAlt, LeftControl, LeftMeta, LeftShift};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Margin, Rect, Size};
+use ratatui::style::{Color, Style};
+use std::collections::HashMap;
 use std::sync::mpsc::Sender;
-#[cfg(feature = "web")]
-use web_time::Instant;
-#[cfg(not(feature = "web"))]
-use std::time::Instant;
+use crate::clock::{Clock, WallClock};
 use tachyonfx::fx::consume_tick;
-use tachyonfx::{fx, CellFilter, Duration, Effect, EffectManager, Interpolation};
+use tachyonfx::{fx, CellFilter, Duration, Effect, EffectManager, Interpolation, SimpleRng};
 
 pub struct ExabindApp {
     running: bool,
     keymap_context: KeyMapContext,
     sender: Sender<ExabindEvent>,
-    last_tick: Instant,
+    clock: Box<dyn Clock>,
+    rng: SimpleRng,
+    effect_policy: EffectPolicy,
+    effect_overrides: EffectOverrides,
     input_processor: InputProcessor,
     effects: EffectManager<UniqueEffectId>,
     stateful_widgets: StatefulWidgets,
@@ -39,6 +44,7 @@ pub struct KeyMapContext {
     pub filter_key_alt: bool,
     pub filter_key_shift: bool,
     pub filter_key_meta: bool,
+    category_colors: HashMap<String, Color>,
 }
 
 impl KeyMapContext {
@@ -91,6 +97,21 @@ impl KeyMapContext {
         Some(self.ordered_categories[self.current_category?])
     }
 
+    /// Base colour of a category; the theme's colour for the category index,
+    /// unless overridden when creating the app.
+    pub fn category_base_color(&self, category_idx: usize) -> Color {
+        let (category, _) = &self.categories[category_idx];
+        self.category_colors.get(category)
+            .copied()
+            .unwrap_or_else(|| theme().shortcuts_base_color(category_idx))
+    }
+
+    /// Style of the key cap outlines of the selected category.
+    pub fn selected_category_outline(&self) -> Style {
+        let category_idx = self.sorted_category_idx().expect("no category selected");
+        theme().kbd_cap_outline(self.category_base_color(category_idx))
+    }
+
     pub fn current_modifier_keys(&self) -> Vec<KeyCap> {
        let layout = AnsiKeyboardTklLayout
            .layout();
@@ -128,6 +149,17 @@ impl KeyMapContext {
         }
     }
 
+    fn active_modifiers(&self) -> Vec<ModifierKeyCode> {
+        [
+            (self.filter_key_control, LeftControl),
+            (self.filter_key_shift,   LeftShift),
+            (self.filter_key_alt,     LeftAlt),
+            (self.filter_key_meta,    LeftMeta),
+        ].into_iter()
+            .filter_map(|(active, modifier)| active.then_some(modifier))
+            .collect()
+    }
+
     pub fn filtered_actions(&self) -> Vec<BoundShortcut> {
         match self.category() {
             Some(category) => self.filtered_actions_by_category(category).1,
@@ -138,20 +170,9 @@ impl KeyMapContext {
     pub fn filtered_actions_by_category(&self, category: &str) -> (usize, Vec<BoundShortcut>) {
         let keymap = &self.keymap;
 
-        let uses_any_modifier_keys = || -> bool {
-            self.filter_key_control
-                || self.filter_key_shift
-                || self.filter_key_alt
-                || self.filter_key_meta
-        };
-
+        let active_modifiers = self.active_modifiers();
         let uses_active_modifier_keys = |shortcut: &Shortcut| -> bool {
-            !uses_any_modifier_keys() || (
-                self.filter_key_control  == shortcut.uses_modifier(LeftControl)
-                    && self.filter_key_shift == shortcut.uses_modifier(LeftShift)
-                    && self.filter_key_alt   == shortcut.uses_modifier(LeftAlt)
-                    && self.filter_key_meta  == shortcut.uses_modifier(LeftMeta)
-            )
+            active_modifiers.is_empty() || shortcut.uses_exactly_modifiers(&active_modifiers)
         };
 
         let index_of_category = keymap.categories().iter().position(|(cat, _)| cat == category).unwrap();
@@ -175,6 +196,17 @@ impl ExabindApp {
         ui_state: &mut UiState,
         sender: Sender<ExabindEvent>,
         keymap: KeyMap,
+    ) -> Self {
+        Self::with_category_colors(ui_state, sender, keymap, HashMap::new())
+    }
+
+    /// Creates the app with the base colours of some categories overridden,
+    /// e.g. to colour a [diff](crate::diff::KeymapDiff) by kind of change.
+    pub fn with_category_colors(
+        ui_state: &mut UiState,
+        sender: Sender<ExabindEvent>,
+        keymap: KeyMap,
+        category_colors: HashMap<String, Color>,
     ) -> Self {
         let categories = keymap.categories();
         let ordered_categories = if categories.is_empty() {
@@ -191,6 +223,7 @@ impl ExabindApp {
             filter_key_alt: false,
             filter_key_shift: false,
             filter_key_meta: false,
+            category_colors,
             keymap,
         };
         let mut widgets = StatefulWidgets::new(&keymap_context, sender.clone());
@@ -200,12 +233,64 @@ impl ExabindApp {
             input_processor: InputProcessor::new(sender.clone()),
             sender,
             keymap_context,
-            last_tick: Instant::now(),
+            clock: Box::new(WallClock::new()),
+            rng: SimpleRng::default(),
+            effect_policy: EffectPolicy::default(),
+            effect_overrides: EffectOverrides::default(),
             effects: EffectManager::default(),
             stateful_widgets: widgets,
         }
     }
 
+    /// Replaces the wall clock, e.g. with a [FixedClock](crate::clock::FixedClock)
+    /// to render frames deterministically.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Seeds the randomness of the effects, which otherwise differ each run.
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = SimpleRng::new(seed);
+        self
+    }
+
+    /// Selects which effects to play, e.g. none of the animations with reduced
+    /// motion.
+    pub fn with_effect_policy(mut self, effect_policy: EffectPolicy) -> Self {
+        self.effect_policy = effect_policy;
+        self
+    }
+
+    /// Replaces built-in effects with those from the config file.
+    pub fn with_effect_overrides(mut self, effect_overrides: EffectOverrides) -> Self {
+        self.effect_overrides = effect_overrides;
+        self
+    }
+
+    /// Plays the startup animation and opens all category widgets, after which
+    /// the first category is selected; with reduced motion, the first category
+    /// is selected right away.
+    pub fn start_up(&mut self, ui_state: &mut UiState) {
+        if !self.effect_policy.animations() {
+            self.dispatch(ExabindEvent::AutoSelectCategory);
+            return;
+        }
+
+        let led_border = self.effect_policy.led_border();
+        ui_state.register_kbd_effect(starting_up(&mut self.rng, led_border, &self.effect_overrides));
+
+        let widgets = self.stateful_widgets.category_widgets();
+        let open_categories_fx = effect::open_all_categories(
+            self.sender.clone(), widgets, &mut self.rng, &self.effect_overrides
+        );
+        self.effects.add_effect(open_categories_fx);
+    }
+
+    pub fn effect_overrides(&self) -> &EffectOverrides {
+        &self.effect_overrides
+    }
+
     pub fn keymap_context(&self) -> &KeyMapContext {
         &self.keymap_context
     }
@@ -231,10 +316,7 @@ impl ExabindApp {
     }
 
     pub fn update_time(&mut self) -> Duration {
-        let now = Instant::now();
-        let last_frame_duration: Duration = now.duration_since(self.last_tick).into();
-        self.last_tick = now;
-        last_frame_duration
+        self.clock.tick()
     }
 
     pub fn process_effects(&mut self, last_frame_duration: Duration, buf: &mut Buffer, area: Rect) {
@@ -250,7 +332,12 @@ impl ExabindApp {
             Tick                      => (),
             Shutdown                  => self.running = false,
             KeyPress(_)               => self.input_processor.apply(&event),
-            StartupAnimation          => ui_state.register_kbd_effect(starting_up()),
+            StartupAnimation          => {
+                if self.effect_policy.animations() {
+                    let led_border = self.effect_policy.led_border();
+                    ui_state.register_kbd_effect(starting_up(&mut self.rng, led_border, &self.effect_overrides));
+                }
+            },
             AutoSelectCategory => {
                 if self.keymap_context.category().is_none() {
                     self.dispatch(NextCategory)
@@ -285,14 +372,18 @@ impl ExabindApp {
                 let size = ui_state.kbd_size();
                 let stage = ui_state.kbd_effects_mut();
                 if self.keymap_context.current_category.is_some() {
-                    let fx = outline_selected_category_key_caps(stage, self.keymap_context(), size);
+                    let animated = self.effect_policy.animations();
+                    let fx = outline_selected_category_key_caps(stage, &self.keymap_context, size, &mut self.rng, animated);
                     stage.add_effect(fx);
                 }
             },
+            SelectedCategoryFxSandbox if !self.effect_policy.animations() => (),
             SelectedCategoryFxSandbox => {
                 let widget = self.stateful_widgets.selected_category_widget(&self.keymap_context);
                 let area = widget.area();
-                let fx = effect::open_category(widget.bg_color(), area);
+                let fx = self.effect_overrides.effect(EffectEvent::CategoryOpened, widget.bg_color())
+                    .map(|fx| fx.with_area(area))
+                    .unwrap_or_else(|| effect::open_category(widget.bg_color(), area, &mut self.rng));
                 self.register_effect(fx);
             }
         }
@@ -310,12 +401,22 @@ impl ExabindApp {
 
         let area = widget.area();
         let color = widget.border_color();
-        let fx = fx::parallel(&[
-            effect::selected_category(color, area),
-            fx::fade_from_fg(color, (200, Interpolation::BounceInOut))
-                .with_area(area)
-                .with_filter(CellFilter::Outer(Margin::new(1, 1))),
-        ]) ;
+        if !self.effect_policy.animations() {
+            let highlight = theme().shortcuts_widget_label().fg.unwrap_or(color);
+            let fx = effect::highlight_selected_category(highlight, area);
+            self.stage_mut().add_unique_effect(UniqueEffectId::SelectedCategory, fx);
+            return;
+        }
+
+        let fx = match self.effect_overrides.effect(EffectEvent::CategorySelected, color) {
+            Some(fx) => fx.with_area(area),
+            None => fx::parallel(&[
+                effect::selected_category(color, area),
+                fx::fade_from_fg(color, (200, Interpolation::BounceInOut))
+                    .with_area(area)
+                    .with_filter(CellFilter::Outer(Margin::new(1, 1))),
+            ]),
+        };
 
         self.stage_mut().add_unique_effect(UniqueEffectId::SelectedCategory, fx);
     }
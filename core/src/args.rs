@@ -1,22 +1,301 @@
-use std::path::PathBuf;
-use clap::Parser;
+use ratatui::layout::Size;
+use std::path::{Path, PathBuf};
+use clap::builder::PossibleValuesParser;
+use clap::{Parser, Subcommand};
+use tachyonfx::Duration;
+#[cfg(feature = "serde")]
+use crate::export::ExportFormat;
+use crate::config::Config;
+use crate::fx::overrides::EffectOverrides;
+use crate::fx::policy::EffectPolicy;
+use crate::key_event::ModifierKeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::{self, KeymapFormat};
+use crate::recording::{RecordFormat, RecordOptions};
+use crate::shortcut::Shortcut;
+use crate::styling::{ColorDepth, Theme};
 
 /// Exabind - A keyboard shortcut visualization tool
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 pub struct Args {
-    /// Path to KDE global shortcuts file (typically ~/.config/kglobalshortcutsrc)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Paths to the keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+    pub files: Vec<PathBuf>,
+
+    /// Path to a keymap file; may be repeated, same as the positional paths
     #[arg(short, long)]
-    pub shortcuts_file: Option<PathBuf>,
+    pub shortcuts_file: Vec<PathBuf>,
+
+    /// Format of the keymap files (detected from each file when omitted)
+    #[arg(short, long, global = true,
+        value_parser = PossibleValuesParser::new(parser::FORMATS.iter().map(|format| format.name())))]
+    pub keymap_format: Option<String>,
+
+    /// Built-in theme, or path to a TOML theme file or a base16 scheme
+    #[arg(long, global = true, default_value = "catppuccin-mocha",
+        long_help = theme_help())]
+    pub theme: String,
+
+    /// Colours of the terminal (detected from COLORTERM and terminfo when omitted)
+    #[arg(long, global = true,
+        value_parser = PossibleValuesParser::new(ColorDepth::ALL.iter().map(|depth| depth.name())))]
+    pub colors: Option<String>,
+
+    /// Replace animations with instant changes; also set by `reduced_motion` in the config file
+    #[arg(long, global = true)]
+    pub reduced_motion: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show the actions added, removed and rebound between two keymaps
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// Browse the changes on the keyboard instead of printing them
+        #[arg(long)]
+        tui: bool,
+    },
+
+    /// Print the actions of the keymaps
+    List {
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+
+        /// Only list actions of this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Only list shortcuts pressed with exactly these modifiers
+        #[arg(short, long, value_parser = ["ctrl", "shift", "alt", "meta"])]
+        modifier: Vec<String>,
+    },
+
+    /// Print the actions bound to a shortcut, e.g. "Meta+Shift+Left"
+    Query {
+        shortcut: String,
+
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+    },
+
+    /// Print conflicting and shadowed shortcuts; exits with 1 if there are any
+    Conflicts {
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+    },
+
+    /// Draw the keyboard as an SVG image, with the keys of the shortcuts outlined
+    Svg {
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+
+        /// Only outline the keys of this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Show these modifiers pressed, outlining only shortcuts pressed with exactly them
+        #[arg(short, long, value_parser = ["ctrl", "shift", "alt", "meta"])]
+        modifier: Vec<String>,
+
+        /// Write the image to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Record exabind starting up, without a terminal, as an asciinema cast or ANSI text
+    Record {
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+
+        /// Output format; "ansi" writes the last frame
+        #[arg(short, long, default_value = "cast",
+            value_parser = PossibleValuesParser::new(RecordFormat::ALL.iter().map(|format| format.name())))]
+        format: String,
+
+        /// Write the recording to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Width of the screen, in columns
+        #[arg(long, default_value_t = 160)]
+        width: u16,
+
+        /// Height of the screen, in rows
+        #[arg(long, default_value_t = 48)]
+        height: u16,
+
+        /// Frames per second of the recording, at most 1000
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=1000))]
+        fps: u32,
+
+        /// Length of the recording, in seconds
+        #[arg(short, long, default_value_t = 6.0)]
+        duration: f32,
+
+        /// Select the next category every this many seconds
+        #[arg(long)]
+        cycle_categories: Option<f32>,
+    },
+
+    /// Print the shortcuts of the keymaps as JSON, YAML or CSV, or as a Markdown or HTML cheat sheet
+    #[cfg(feature = "serde")]
+    Export {
+        /// Keymap files (defaults to KDE's ~/.config/kglobalshortcutsrc)
+        files: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, default_value = "json",
+            value_parser = PossibleValuesParser::new(ExportFormat::ALL.iter().map(|format| format.name())))]
+        format: String,
+    },
+}
+
+/// A keymap file, along with its format and the keymap parsed from it.
+pub struct KeymapSource {
+    pub path: PathBuf,
+    pub format: &'static dyn KeymapFormat,
+    keymap: Box<KeyMap>,
+}
+
+impl KeymapSource {
+    pub fn keymap(&self) -> KeyMap {
+        (*self.keymap).clone()
+    }
+}
+
+/// What exabind was asked to do, with all keymap files resolved.
+pub enum Invocation {
+    /// Browse one or more keymaps.
+    View(Vec<KeymapSource>),
+    Diff { old: KeymapSource, new: KeymapSource, tui: bool },
+    List { sources: Vec<KeymapSource>, category: Option<String>, modifiers: Vec<ModifierKeyCode> },
+    Query { sources: Vec<KeymapSource>, shortcut: Shortcut },
+    Conflicts(Vec<KeymapSource>),
+    Svg { sources: Vec<KeymapSource>, category: Option<String>, modifiers: Vec<ModifierKeyCode>, output: Option<PathBuf> },
+    Record { sources: Vec<KeymapSource>, options: RecordOptions, format: RecordFormat, output: Option<PathBuf> },
+    #[cfg(feature = "serde")]
+    Export { sources: Vec<KeymapSource>, format: ExportFormat },
 }
 
-pub fn parse_args() -> Result<PathBuf, String> {
+/// The parsed command line.
+pub struct Cli {
+    pub invocation: Invocation,
+    pub theme: Theme,
+    /// Colour depth to render with; detected when `None`.
+    pub color_depth: Option<ColorDepth>,
+    /// Effects of the interactive views, from the config file and the arguments.
+    pub effect_policy: EffectPolicy,
+    /// Effects replacing the built-in ones, from the config file.
+    pub effect_overrides: EffectOverrides,
+}
+
+pub fn parse_args() -> Result<Cli, String> {
     let args = Args::parse();
+    let theme = Theme::load(&args.theme)?;
+    let color_depth = args.colors.as_deref()
+        .map(|name| ColorDepth::from_name(name).ok_or(format!("Unsupported colors: {name}")))
+        .transpose()?;
+
+    let config = Config::load()?;
+    let effect_policy = EffectPolicy {
+        reduced_motion: args.reduced_motion || config.effect_policy.reduced_motion,
+        ..config.effect_policy
+    };
+    let effect_overrides = config.effect_overrides;
+    let format = args.keymap_format.as_deref();
+
+    let invocation = match args.command {
+        Some(Command::Diff { old, new, tui }) => Invocation::Diff {
+            old: keymap_source(old, format)?,
+            new: keymap_source(new, format)?,
+            tui,
+        },
+        Some(Command::List { files, category, modifier }) => Invocation::List {
+            sources: keymap_sources(files, format)?,
+            category,
+            modifiers: modifier.iter().map(String::as_str).map(modifier_key_code).collect(),
+        },
+        Some(Command::Query { shortcut, files }) => Invocation::Query {
+            sources: keymap_sources(files, format)?,
+            shortcut: parser::parse_shortcut(&shortcut)
+                .ok_or(format!("Could not parse shortcut: {shortcut}"))?,
+        },
+        Some(Command::Conflicts { files }) => Invocation::Conflicts(keymap_sources(files, format)?),
+        Some(Command::Svg { files, category, modifier, output }) => Invocation::Svg {
+            sources: keymap_sources(files, format)?,
+            category,
+            modifiers: modifier.iter().map(String::as_str).map(modifier_key_code).collect(),
+            output,
+        },
+        Some(Command::Record { files, format: record_format, output, width, height, fps, duration, cycle_categories }) => Invocation::Record {
+            sources: keymap_sources(files, format)?,
+            options: RecordOptions {
+                size: Size::new(width, height),
+                fps,
+                duration: seconds(duration),
+                category_interval: cycle_categories.map(seconds),
+                effect_policy,
+                effect_overrides: effect_overrides.clone(),
+            },
+            format: RecordFormat::from_name(&record_format)
+                .ok_or(format!("Unsupported record format: {record_format}"))?,
+            output,
+        },
+        #[cfg(feature = "serde")]
+        Some(Command::Export { files, format: export_format }) => Invocation::Export {
+            sources: keymap_sources(files, format)?,
+            format: ExportFormat::from_name(&export_format)
+                .ok_or(format!("Unsupported export format: {export_format}"))?,
+        },
+        None => {
+            let mut paths = args.files;
+            paths.extend(args.shortcuts_file);
+            Invocation::View(keymap_sources(paths, format)?)
+        },
+    };
+
+    Ok(Cli { invocation, theme, color_depth, effect_policy, effect_overrides })
+}
+
+fn theme_help() -> String {
+    format!(
+        "Built-in theme, or path to a TOML theme file or a base16 scheme (.yaml)\n\n\
+        Built-in themes: {}; \"terminal\" uses the terminal's own 16 colours",
+        Theme::names().join(", ")
+    )
+}
+
+/// Resolves the keymap files, falling back to KDE's global shortcuts if none
+/// were given.
+fn keymap_sources(mut paths: Vec<PathBuf>, format: Option<&str>) -> Result<Vec<KeymapSource>, String> {
+    if paths.is_empty() {
+        paths.push(PathBuf::from("~/.config/kglobalshortcutsrc"));
+    }
 
-    // use provided path or fall back to default
-    let shortcuts_path = args.shortcuts_file
-        .unwrap_or(PathBuf::from("~/.config/kglobalshortcutsrc"));
+    paths.into_iter()
+        .map(|path| keymap_source(path, format))
+        .collect()
+}
+
+fn modifier_key_code(name: &str) -> ModifierKeyCode {
+    match name {
+        "ctrl"  => ModifierKeyCode::LeftControl,
+        "shift" => ModifierKeyCode::LeftShift,
+        "alt"   => ModifierKeyCode::LeftAlt,
+        _       => ModifierKeyCode::LeftMeta,
+    }
+}
 
+fn seconds(seconds: f32) -> Duration {
+    Duration::from_millis((seconds.max(0.0) * 1000.0) as u32)
+}
+
+fn keymap_source(shortcuts_path: PathBuf, format: Option<&str>) -> Result<KeymapSource, String> {
     // expand tilde if present
     let expanded_path = if shortcuts_path.to_string_lossy().starts_with('~') {
         if let Some(home) = dirs::home_dir() {
@@ -32,10 +311,46 @@ pub fn parse_args() -> Result<PathBuf, String> {
     // verify file exists
     if !expanded_path.exists() {
         return Err(format!(
-            "Shortcuts file not found at: {}\nProvide path with --shortcuts-file or place file at default location",
+            "Shortcuts file not found at: {}\nProvide a path or place file at default location",
             expanded_path.display()
         ));
     }
 
-    Ok(expanded_path)
+    let input = std::fs::read_to_string(&expanded_path)
+        .map_err(|e| format!("Could not read {}: {e}", expanded_path.display()))?;
+
+    let format = match format {
+        Some(name) => parser::format(name)
+            .ok_or(format!("Unsupported format: {name}"))?,
+        None => detect_format(&expanded_path, &input)?,
+    };
+
+    let keymap = format.parse(&input)
+        .map(Box::new)
+        .map_err(|e| format!("Could not parse {}: {e}", expanded_path.display()))?;
+
+    Ok(KeymapSource { path: expanded_path, format, keymap })
+}
+
+fn detect_format(path: &Path, input: &str) -> Result<&'static dyn KeymapFormat, String> {
+    parser::detect_format(path, input).ok_or_else(|| {
+        let formats: Vec<&str> = parser::FORMATS.iter().map(|format| format.name()).collect();
+        format!(
+            "Could not detect the format of {}\nSelect one with --keymap-format: {}",
+            path.display(),
+            formats.join(", ")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_fps_range() {
+        assert!(Args::try_parse_from(["exabind", "record", "--fps", "1000"]).is_ok());
+        assert!(Args::try_parse_from(["exabind", "record", "--fps", "2000"]).is_err());
+        assert!(Args::try_parse_from(["exabind", "record", "--fps", "0"]).is_err());
+    }
 }
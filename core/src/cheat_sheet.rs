@@ -0,0 +1,164 @@
+use crate::keymap::KeyMap;
+use crate::shortcut::{Action, Shortcut};
+use crate::styling::{theme, ExabindTheme};
+use crate::svg::{css_color, escape, keymap_svg};
+use ratatui::style::Color;
+use std::fmt::Write;
+
+/// A category of the cheat sheet, listed like the TUI's shortcuts widgets:
+/// one row per shortcut, in the order of the actions.
+struct Category<'a> {
+    name: &'a str,
+    base_color: Color,
+    shortcuts: Vec<(&'a Shortcut, &'a Action)>,
+}
+
+/// The categories of the keymap, sorted by name and coloured by the theme's
+/// category palette.
+fn categories(keymap: &KeyMap) -> Vec<Category<'_>> {
+    keymap.category_names().into_iter()
+        .enumerate()
+        .map(|(idx, name)| Category {
+            name,
+            base_color: theme().shortcuts_base_color(idx),
+            shortcuts: keymap.actions_by_category(name).iter()
+                .flat_map(|action| action.shortcuts().iter().map(move |shortcut| (shortcut, action)))
+                .collect(),
+        })
+        .collect()
+}
+
+/// A Markdown cheat sheet with a table per category.
+pub fn markdown(keymap: &KeyMap) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# {}", markdown_escape(keymap.name()));
+
+    for category in categories(keymap) {
+        let _ = writeln!(md, "\n## {}\n", markdown_escape(category.name));
+        let _ = writeln!(md, "| Shortcut | Action |");
+        let _ = writeln!(md, "|----------|--------|");
+        for (shortcut, action) in category.shortcuts {
+            let _ = writeln!(md, "| {} | {} |", code_span(&shortcut.to_string()), markdown_escape(action.name()));
+        }
+    }
+
+    md
+}
+
+/// A self-contained HTML cheat sheet; each category has a table and a
+/// keyboard with the category's keys outlined in its colour.
+pub fn html(keymap: &KeyMap) -> String {
+    let surface = theme().kbd_surface().bg.unwrap_or(Color::Reset);
+    let label = theme().shortcuts_widget_label().fg.unwrap_or(Color::Reset);
+    let keystroke = theme().shortcuts_widget_keystroke().fg.unwrap_or(Color::Reset);
+
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, r#"<html lang="en">"#);
+    let _ = writeln!(html, "<head>");
+    let _ = writeln!(html, r#"<meta charset="utf-8">"#);
+    let _ = writeln!(html, "<title>{} shortcuts</title>", escape(keymap.name()));
+    let _ = writeln!(html, "<style>");
+    let _ = writeln!(html, "body {{ background: {}; color: {}; font-family: sans-serif; margin: 2em; }}",
+        css_color(surface), css_color(label));
+    let _ = writeln!(html, "section {{ break-inside: avoid; margin-bottom: 2em; }}");
+    let _ = writeln!(html, "h2 {{ border-bottom: 2px solid; padding-bottom: 0.2em; }}");
+    let _ = writeln!(html, "svg {{ display: block; max-width: 100%; height: auto; margin: 1em 0; }}");
+    let _ = writeln!(html, "table {{ border-collapse: collapse; }}");
+    let _ = writeln!(html, "td {{ padding: 0.2em 1em 0.2em 0; vertical-align: top; }}");
+    let _ = writeln!(html, "td.keystroke {{ color: {}; font-family: monospace; font-weight: bold; white-space: nowrap; }}",
+        css_color(keystroke));
+    let _ = writeln!(html, "</style>");
+    let _ = writeln!(html, "</head>");
+    let _ = writeln!(html, "<body>");
+    let _ = writeln!(html, "<h1>{}</h1>", escape(keymap.name()));
+
+    for category in categories(keymap) {
+        let outline = theme().kbd_cap_outline(category.base_color).fg.unwrap_or(category.base_color);
+        let background = theme().shortcuts_widget_bg(category.base_color);
+
+        let _ = writeln!(html, "<section>");
+        let _ = writeln!(html, r#"<h2 style="color: {0}; border-color: {0}">{1}</h2>"#,
+            css_color(outline), escape(category.name));
+        html.push_str(&keymap_svg(keymap, Some(category.name), &[]));
+        let _ = writeln!(html, r#"<table style="background: {}">"#, css_color(background));
+        for (shortcut, action) in category.shortcuts {
+            let _ = writeln!(html, r#"<tr><td class="keystroke">{}</td><td>{}</td></tr>"#,
+                escape(&shortcut.to_string()), escape(action.name()));
+        }
+        let _ = writeln!(html, "</table>");
+        let _ = writeln!(html, "</section>");
+    }
+
+    let _ = writeln!(html, "</body>");
+    let _ = writeln!(html, "</html>");
+    html
+}
+
+/// Wraps text in a code span, escaping `|` so it doesn't end the table cell.
+fn code_span(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+    match text.contains('`') {
+        true  => format!("`` {text} ``"),
+        false => format!("`{text}`"),
+    }
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, c| {
+        if "\\`*_[]<>|#".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::keymap::kde_keymap;
+
+    fn keymap() -> KeyMap {
+        let meta = |key| Shortcut::new(vec![Modifier(LeftMeta), key]);
+
+        kde_keymap().with_actions(vec![
+            Action::new_filter_empty("Walk Through Windows", "kwin", vec![meta(Tab), meta(Char('`'))]),
+            Action::new_filter_empty("Peek at Desktop | Dashboard", "plasmashell", vec![meta(Char('d'))]),
+        ])
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(markdown(&keymap()), [
+            "# KDE",
+            "",
+            "## kwin",
+            "",
+            "| Shortcut | Action |",
+            "|----------|--------|",
+            "| `Meta W` | Overview |",
+            "| `Meta ⇥` | Walk Through Windows |",
+            "| `` Meta ` `` | Walk Through Windows |",
+            "",
+            "## plasmashell",
+            "",
+            "| Shortcut | Action |",
+            "|----------|--------|",
+            "| `Meta Q` | Activities |",
+            "| `Meta D` | Peek at Desktop \\| Dashboard |",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_html() {
+        let html = html(&keymap());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<section>").count(), 2);
+        assert_eq!(html.matches("<svg ").count(), 2);
+        assert!(html.contains(r#"<tr><td class="keystroke">Meta W</td><td>Overview</td></tr>"#));
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(feature = "web")]
+use web_time::Instant;
+#[cfg(not(feature = "web"))]
+use std::time::Instant;
+use tachyonfx::Duration;
+
+/// Source of the time passed between frames, which drives all effects.
+pub trait Clock {
+    /// Time passed since the previous tick, or since the clock was created.
+    fn tick(&mut self) -> Duration;
+}
+
+/// Measures the time between frames, as the interactive front-ends do.
+pub struct WallClock {
+    last_tick: Instant,
+}
+
+impl WallClock {
+    pub fn new() -> Self {
+        Self { last_tick: Instant::now() }
+    }
+}
+
+impl Default for WallClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for WallClock {
+    fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        let last_frame_duration: Duration = now.duration_since(self.last_tick).into();
+        self.last_tick = now;
+        last_frame_duration
+    }
+}
+
+/// Advances by the same step every tick, making effects deterministic. The
+/// first tick is zero, so that the first frame shows the app as it starts.
+pub struct FixedClock {
+    step: Duration,
+    started: bool,
+}
+
+impl FixedClock {
+    pub fn new(step: Duration) -> Self {
+        Self { step, started: false }
+    }
+}
+
+impl Clock for FixedClock {
+    fn tick(&mut self) -> Duration {
+        match std::mem::replace(&mut self.started, true) {
+            true  => self.step,
+            false => Duration::from_millis(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let mut clock = FixedClock::new(Duration::from_millis(33));
+        let ticks: Vec<u32> = (0..3).map(|_| clock.tick().as_millis()).collect();
+        assert_eq!(ticks, vec![0, 33, 33]);
+    }
+}
@@ -56,6 +56,16 @@ impl<T> ColorCycle<T> where T: IndexResolver<Color> {
         Self { colors: gradient, _marker: std::marker::PhantomData }
     }
 
+    /// Bands of solid colours, each repeated `len` times, without blending
+    /// between them.
+    pub fn bands(colors: &[(usize, Color)]) -> Self {
+        let colors = colors.iter()
+            .flat_map(|(len, color)| std::iter::repeat_n(*color, *len))
+            .collect();
+
+        Self { colors, _marker: std::marker::PhantomData }
+    }
+
     pub fn color_at(&self, idx: usize) -> &Color {
         T::resolve(idx, &self.colors)
     }
@@ -0,0 +1,94 @@
+use crate::fx::overrides::EffectOverrides;
+use crate::fx::policy::EffectPolicy;
+use std::path::PathBuf;
+use toml::Table;
+
+/// Settings from exabind's config file, e.g. `~/.config/exabind/config.toml`:
+///
+/// ```toml
+/// # replace animations with instant changes
+/// reduced_motion = true
+/// # or only stop cycling the key labels through the LED colours
+/// led_border = false
+///
+/// # replace effects with tachyonfx DSL expressions
+/// [effects]
+/// category-opened = "fx::coalesce((500, ExpoOut))"
+/// ```
+///
+/// Command-line options take precedence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub effect_policy: EffectPolicy,
+    pub effect_overrides: EffectOverrides,
+}
+
+impl Config {
+    /// Path of the config file, in the platform's config directory.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("exabind").join("config.toml"))
+    }
+
+    /// Reads the config file, if there is one.
+    pub fn load() -> Result<Config, String> {
+        let Some(path) = Config::path().filter(|path| path.exists()) else {
+            return Ok(Config::default());
+        };
+
+        let input = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+
+        Config::from_toml(&input)
+            .map_err(|e| format!("Invalid config {}: {e}", path.display()))
+    }
+
+    pub fn from_toml(input: &str) -> Result<Config, String> {
+        let toml: Table = input.parse()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        if let Some(key) = toml.keys().find(|key| !["reduced_motion", "led_border", "effects"].contains(&key.as_str())) {
+            return Err(format!("unknown setting {key}"));
+        }
+
+        let effect_overrides = match toml.get("effects") {
+            Some(toml::Value::Table(effects)) => EffectOverrides::from_table(effects)?,
+            Some(_) => return Err("effects must be a table".to_string()),
+            None    => EffectOverrides::default(),
+        };
+
+        let defaults = EffectPolicy::default();
+        Ok(Config {
+            effect_policy: EffectPolicy {
+                reduced_motion: flag(&toml, "reduced_motion")?.unwrap_or(defaults.reduced_motion),
+                led_border: flag(&toml, "led_border")?.unwrap_or(defaults.led_border),
+            },
+            effect_overrides,
+        })
+    }
+}
+
+fn flag(toml: &Table, key: &str) -> Result<Option<bool>, String> {
+    toml.get(key)
+        .map(|value| value.as_bool().ok_or(format!("{key} must be true or false")))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_toml() {
+        assert_eq!(Config::from_toml(""), Ok(Config::default()));
+
+        let config = Config::from_toml("led_border = false").unwrap();
+        assert_eq!(config.effect_policy, EffectPolicy { reduced_motion: false, led_border: false });
+
+        assert_eq!(Config::from_toml("reduced_motion = 1"), Err("reduced_motion must be true or false".to_string()));
+        let config = Config::from_toml("[effects]\nstartup = \"fx::dissolve(500)\"").unwrap();
+        assert!(!config.effect_overrides.is_empty());
+        assert_eq!(Config::from_toml("effects = 1"), Err("effects must be a table".to_string()));
+
+        assert_eq!(Config::from_toml("reduce_motion = true"), Err("unknown setting reduce_motion".to_string()));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::keymap::KeyMap;
+use crate::shortcut::{Action, Shortcut};
+use crate::styling::{theme, ExabindTheme};
+use ratatui::style::Color;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+
+/// How an action changed between two keymaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// The action exists in both keymaps, but with different shortcuts.
+    Rebound,
+}
+
+impl ChangeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Added   => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Rebound => "rebound",
+        }
+    }
+
+    fn marker(&self) -> char {
+        match self {
+            ChangeKind::Added   => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Rebound => '~',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionChange {
+    pub category: String,
+    pub name: String,
+    pub kind: ChangeKind,
+    pub old: Vec<Shortcut>,
+    pub new: Vec<Shortcut>,
+}
+
+/// The actions added, removed and rebound between two keymaps. Actions are
+/// matched by category and [id](Action::id), so that a relabelled action is
+/// still recognized; changes are shown with the action's newest label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeymapDiff {
+    changes: Vec<ActionChange>,
+}
+
+impl KeymapDiff {
+    pub fn new(old: &KeyMap, new: &KeyMap) -> Self {
+        let old_actions = shortcuts_by_action(old);
+        let mut new_actions = shortcuts_by_action(new);
+
+        let mut changes = Vec::new();
+        for ((category, id), old) in old_actions {
+            let change = |kind, name: &str, new| ActionChange {
+                category: category.to_string(),
+                name: name.to_string(),
+                kind,
+                old: old.shortcuts.clone(),
+                new,
+            };
+
+            match new_actions.remove(&(category, id)) {
+                None => changes.push(change(ChangeKind::Removed, old.name, Vec::new())),
+                Some(new) if new.shortcuts != old.shortcuts => {
+                    changes.push(change(ChangeKind::Rebound, new.name, new.shortcuts))
+                },
+                Some(_) => (),
+            }
+        }
+
+        changes.extend(new_actions.into_iter().map(|((category, _), new)| ActionChange {
+            category: category.to_string(),
+            name: new.name.to_string(),
+            kind: ChangeKind::Added,
+            old: Vec::new(),
+            new: new.shortcuts,
+        }));
+
+        changes.sort_by(|a, b| (&a.category, &a.name).cmp(&(&b.category, &b.name)));
+        Self { changes }
+    }
+
+    pub fn changes(&self) -> &[ActionChange] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes as a keymap, with a category per original category and
+    /// kind of change, e.g. "kwin: added". Added and rebound actions carry
+    /// their new shortcuts, removed actions their old ones.
+    pub fn to_keymap(&self) -> KeyMap {
+        let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+        for change in &self.changes {
+            let category = diff_category(&change.category, change.kind);
+            let (label, shortcuts) = match change.kind {
+                ChangeKind::Added   => (change.name.clone(), change.new.clone()),
+                ChangeKind::Removed => (change.name.clone(), change.old.clone()),
+                ChangeKind::Rebound => {
+                    let label = format!("{} (was {})", change.name, joined(&change.old));
+                    (label, change.new.clone())
+                },
+            };
+
+            actions.entry(category.clone())
+                .or_default()
+                .push(Action::new_filter_empty(label, category, shortcuts));
+        }
+
+        KeyMap::new("diff", actions)
+    }
+
+    /// Colours for the categories of [Self::to_keymap], by kind of change.
+    pub fn category_colors(&self) -> HashMap<String, Color> {
+        self.changes.iter()
+            .map(|change| (diff_category(&change.category, change.kind), theme().change_color(change.kind)))
+            .collect()
+    }
+}
+
+impl Display for KeymapDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut category = None;
+        for change in &self.changes {
+            if category != Some(&change.category) {
+                category = Some(&change.category);
+                writeln!(f, "{}", change.category)?;
+            }
+
+            let shortcuts = match change.kind {
+                ChangeKind::Added   => joined(&change.new),
+                ChangeKind::Removed => joined(&change.old),
+                ChangeKind::Rebound => format!("{} -> {}", joined(&change.old), joined(&change.new)),
+            };
+
+            writeln!(f, "  {} {}: {}", change.kind.marker(), change.name, shortcuts)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn diff_category(category: &str, kind: ChangeKind) -> String {
+    format!("{}: {}", category, kind.name())
+}
+
+fn joined(shortcuts: &[Shortcut]) -> String {
+    shortcuts.iter()
+        .map(Shortcut::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The label and all shortcuts of an action.
+#[derive(Default)]
+struct BoundAction<'a> {
+    name: &'a str,
+    shortcuts: Vec<Shortcut>,
+}
+
+/// All shortcuts of each action, keyed by category and action id; actions
+/// sharing an id within a category are treated as one.
+fn shortcuts_by_action(keymap: &KeyMap) -> BTreeMap<(&str, &str), BoundAction<'_>> {
+    let mut actions: BTreeMap<(&str, &str), BoundAction> = BTreeMap::new();
+    for action in keymap.actions() {
+        let bound = actions.entry((action.category(), action.id()))
+            .or_default();
+
+        bound.name = action.name();
+        bound.shortcuts.extend(action.shortcuts().iter().cloned());
+    }
+
+    actions.values_mut().for_each(|action| {
+        action.shortcuts.sort_by_key(Shortcut::to_string);
+        action.shortcuts.dedup();
+    });
+
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+
+    fn keymap(actions: &[(&str, char)]) -> KeyMap {
+        let actions = actions.iter()
+            .map(|(name, c)| Action::new_filter_empty(*name, "kwin", vec![
+                Shortcut::new(vec![Modifier(LeftMeta), Char(*c)]),
+            ]))
+            .collect();
+
+        KeyMap::new("KDE", HashMap::from([("kwin".to_string(), actions)]))
+    }
+
+    #[test]
+    fn test_keymap_diff() {
+        let old = keymap(&[("Overview", 'w'), ("Lock Session", 'l'), ("Show Desktop", 'd')]);
+        let new = keymap(&[("Overview", 'w'), ("Lock Session", 'x'), ("Grid View", 'g')]);
+
+        let diff = KeymapDiff::new(&old, &new);
+        let changes: Vec<(&str, ChangeKind)> = diff.changes().iter()
+            .map(|change| (change.name.as_str(), change.kind))
+            .collect();
+
+        assert_eq!(changes, vec![
+            ("Grid View", ChangeKind::Added),
+            ("Lock Session", ChangeKind::Rebound),
+            ("Show Desktop", ChangeKind::Removed),
+        ]);
+
+        let keymap = diff.to_keymap();
+        assert_eq!(keymap.categories(), vec![
+            ("kwin: added".to_string(), 1),
+            ("kwin: rebound".to_string(), 1),
+            ("kwin: removed".to_string(), 1),
+        ]);
+
+        let rebound = keymap.actions_by_category("kwin: rebound");
+        assert_eq!(rebound[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('x')])]);
+        assert!(KeymapDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_relabelled_action_is_rebound() {
+        let action = |label: &str, c: char| Action::new_filter_empty("show desktop", "kwin", vec![
+            Shortcut::new(vec![Modifier(LeftMeta), Char(c)]),
+        ]).with_label(label);
+
+        let old = KeyMap::new("KDE", HashMap::from([("kwin".to_string(), vec![action("Show Desktop", 'd')])]));
+        let new = KeyMap::new("KDE", HashMap::from([("kwin".to_string(), vec![action("Peek at Desktop", 'x')])]));
+
+        let changes = KeymapDiff::new(&old, &new).changes;
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].name.as_str(), changes[0].kind), ("Peek at Desktop", ChangeKind::Rebound));
+
+        let relabelled = KeyMap::new("KDE", HashMap::from([("kwin".to_string(), vec![action("Peek at Desktop", 'd')])]));
+        assert!(KeymapDiff::new(&old, &relabelled).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::cheat_sheet;
+use crate::keymap::KeyMap;
+use crate::parser::shortcut_notation;
+use serde::{Deserialize, Serialize};
+
+/// Formats a keymap can be exported as: the [records] of the keymap, or a
+/// printable [cheat sheet](crate::cheat_sheet).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: &'static [ExportFormat] = &[
+        ExportFormat::Json,
+        ExportFormat::Yaml,
+        ExportFormat::Csv,
+        ExportFormat::Markdown,
+        ExportFormat::Html,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Json     => "json",
+            ExportFormat::Yaml     => "yaml",
+            ExportFormat::Csv      => "csv",
+            ExportFormat::Markdown => "markdown",
+            ExportFormat::Html     => "html",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|format| format.name() == name)
+    }
+}
+
+/// One shortcut of an action, along with the default shortcut in the same
+/// position; the unit of the export schema. An action with several shortcuts
+/// or defaults is exported as one record per position, and an action without
+/// shortcuts, but with defaults, as records with empty `keystrokes`.
+///
+/// | field        | description                                                       |
+/// |--------------|-------------------------------------------------------------------|
+/// | `source`     | name of the keymap the action was read from, e.g. `KDE`           |
+/// | `category`   | category of the action within its source, e.g. `kwin`             |
+/// | `id`         | identifier of the action, as written in the keymap file           |
+/// | `label`      | human-readable name; the id when the format has no separate label |
+/// | `keystrokes` | the shortcut, e.g. `Meta+Shift+Left` or `Ctrl+x Ctrl+e`; else empty |
+/// | `default`    | default shortcut in the same position, if known; else empty         |
+///
+/// Keystrokes are separated by spaces and keys by `+`, using the key names
+/// accepted by `exabind query`; `+` and space are spelled `Plus` and `Space`.
+///
+/// When [importing](crate::parser::exabind), `source`, `label` and `default`
+/// may be left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortcutRecord {
+    #[serde(default)]
+    pub source: String,
+    pub category: String,
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    pub keystrokes: String,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// The shortcuts and default shortcuts of all actions of the keymap,
+/// including its [unbound actions](KeyMap::unbound_actions), ordered by
+/// source, category and id.
+pub fn records(keymap: &KeyMap) -> Vec<ShortcutRecord> {
+    let mut records: Vec<ShortcutRecord> = keymap.actions()
+        .chain(keymap.unbound_actions())
+        .flat_map(|action| {
+            let positions = action.shortcuts().len().max(action.default_shortcuts().len());
+            (0..positions).map(move |i| ShortcutRecord {
+                source: action.source().to_string(),
+                category: action.own_category().to_string(),
+                id: action.id().to_string(),
+                label: action.name().to_string(),
+                keystrokes: action.shortcuts().get(i).map(shortcut_notation).unwrap_or_default(),
+                default: action.default_shortcuts().get(i).map(shortcut_notation),
+            })
+        })
+        .collect();
+
+    // stable, so that the shortcuts of an action keep their order
+    records.sort_by(|a, b| (&a.source, &a.category, &a.id).cmp(&(&b.source, &b.category, &b.id)));
+    records
+}
+
+/// Serializes the [records] of the keymap: JSON as an array of objects, YAML
+/// as a sequence of mappings and CSV with a header row. Markdown and HTML
+/// are rendered as cheat sheets instead.
+pub fn export(keymap: &KeyMap, format: ExportFormat) -> Result<String, String> {
+    let records = records(keymap);
+
+    match format {
+        ExportFormat::Json     => serde_json::to_string_pretty(&records)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        ExportFormat::Yaml     => serde_yaml::to_string(&records)
+            .map_err(|e| e.to_string()),
+        ExportFormat::Markdown => Ok(cheat_sheet::markdown(keymap)),
+        ExportFormat::Html     => Ok(cheat_sheet::html(keymap)),
+        ExportFormat::Csv      => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for record in &records {
+                writer.serialize(record).map_err(|e| e.to_string())?;
+            }
+
+            let csv = writer.into_inner().map_err(|e| e.to_string())?;
+            String::from_utf8(csv).map_err(|e| e.to_string())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::shortcut::{Action, Shortcut};
+    use std::collections::HashMap;
+
+    fn keymap() -> KeyMap {
+        let overview = Action::new_filter_empty("Overview", "kwin", vec![
+            Shortcut::new(vec![Modifier(LeftMeta), Char('w')]),
+            Shortcut::new(vec![Modifier(LeftMeta), Char('+')]),
+        ])
+            .with_label("Toggle Overview")
+            .with_default_shortcuts(vec![Shortcut::new(vec![Modifier(LeftMeta), Char('w')])]);
+
+        let save = Action::new_filter_empty("save-buffer", "global", vec![Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('x')],
+            vec![Modifier(LeftControl), Char('s')],
+        ])]);
+
+        let lock = Action::new_filter_empty("Lock Session", "ksmserver", vec![
+            Shortcut::new(vec![Modifier(LeftMeta), Char('l')]),
+        ])
+            .with_default_shortcuts(vec![
+                Shortcut::new(vec![Modifier(LeftMeta), Char('l')]),
+                Shortcut::new(vec![Modifier(LeftAlt), Modifier(LeftControl), Char('l')]),
+            ]);
+
+        let show_desktop = Action::new_filter_empty("Show Desktop", "kwin", vec![])
+            .with_default_shortcuts(vec![Shortcut::new(vec![Modifier(LeftMeta), Char('d')])]);
+
+        KeyMap::merge(vec![
+            KeyMap::new("KDE", HashMap::from([
+                ("kwin".to_string(), vec![overview]),
+                ("ksmserver".to_string(), vec![lock]),
+            ])).with_unbound_actions(vec![show_desktop]),
+            KeyMap::new("Emacs", HashMap::from([("global".to_string(), vec![save])])),
+        ])
+    }
+
+    #[test]
+    fn test_records() {
+        let records = records(&keymap());
+        let rows: Vec<(&str, &str, &str, Option<&str>)> = records.iter()
+            .map(|r| (r.source.as_str(), r.label.as_str(), r.keystrokes.as_str(), r.default.as_deref()))
+            .collect();
+
+        assert_eq!(rows, vec![
+            ("Emacs", "save-buffer", "Ctrl+x Ctrl+s", None),
+            ("KDE", "Lock Session", "Meta+l", Some("Meta+l")),
+            ("KDE", "Lock Session", "", Some("Ctrl+Alt+l")),
+            ("KDE", "Toggle Overview", "Meta+w", Some("Meta+w")),
+            ("KDE", "Toggle Overview", "Meta+Plus", None),
+            ("KDE", "Show Desktop", "", Some("Meta+d")),
+        ]);
+    }
+
+    #[test]
+    fn test_export_csv() {
+        assert_eq!(export(&keymap(), ExportFormat::Csv).unwrap(), [
+            "source,category,id,label,keystrokes,default",
+            "Emacs,global,save-buffer,save-buffer,Ctrl+x Ctrl+s,",
+            "KDE,ksmserver,Lock Session,Lock Session,Meta+l,Meta+l",
+            "KDE,ksmserver,Lock Session,Lock Session,,Ctrl+Alt+l",
+            "KDE,kwin,Overview,Toggle Overview,Meta+w,Meta+w",
+            "KDE,kwin,Overview,Toggle Overview,Meta+Plus,",
+            "KDE,kwin,Show Desktop,Show Desktop,,Meta+d",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_export_json_round_trip() {
+        let json = export(&keymap(), ExportFormat::Json).unwrap();
+        let parsed: Vec<ShortcutRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, records(&keymap()));
+    }
+}
@@ -3,19 +3,16 @@ use crate::color_cycle::{ColorCycle, IndexResolver, PingPongColorCycle, Repeatin
 use crate::dispatcher::Dispatcher;
 use crate::exabind_event::ExabindEvent;
 use crate::fx::key_cap_outline::KeyCapOutline;
-use crate::styling::{Catppuccin, ExabindTheme, Theme, CATPPUCCIN};
+use crate::fx::overrides::{EffectEvent, EffectOverrides};
+use crate::styling::{theme, ExabindTheme};
 use crate::widget::{draw_key_border, render_border_with, AnsiKeyboardTklLayout, KeyCap, KeyboardLayout, ShortcutsWidget};
 use crate::key_event::KeyCode;
 use ratatui::buffer::Cell;
 use ratatui::layout::{Margin, Position, Rect, Size};
 use ratatui::prelude::Buffer;
-use ratatui::style::{Color, Style};
+use ratatui::style::Color;
 use std::fmt::Debug;
 use std::sync::mpsc::Sender;
-#[cfg(feature = "web")]
-use web_time::Instant;
-#[cfg(not(feature = "web"))]
-use std::time::Instant;
 use tachyonfx::fx::{dispatch_event, effect_fn_buf, parallel, prolong_start, sequence, sleep, sweep_in};
 use tachyonfx::Motion::UpToDown;
 use tachyonfx::{color_from_hsl, color_to_hsl, fx, CellFilter, Duration, Effect, EffectManager, EffectTimer, Interpolation, IntoEffect, RangeSampler, SimpleRng};
@@ -41,8 +38,10 @@ pub fn selected_category(
 ) -> Effect {
     let color_cycle = select_category_color_cycle(base_color, 1);
 
-    let effect = fx::effect_fn_buf(Instant::now(), u32::MAX, move |started_at, ctx, buf| {
-        let elapsed = started_at.elapsed().as_secs_f32();
+    // elapsed time follows the effect timeline, not the wall clock
+    let effect = fx::effect_fn_buf(0u32, u32::MAX, move |elapsed_ms, ctx, buf| {
+        *elapsed_ms = elapsed_ms.saturating_add(ctx.last_tick.as_millis());
+        let elapsed = *elapsed_ms as f32 / 1000.0;
 
         // speed n cells/s
         let idx = (elapsed * 30.0) as usize;
@@ -76,11 +75,33 @@ pub fn selected_category(
     effect.with_area(area)
 }
 
+/// Highlights the border of the selected category in a single color, without
+/// animation.
+///
+/// # Arguments
+/// * `color` - Color of the border
+/// * `area` - The rectangular area of the category widget
+///
+/// # Returns
+/// An Effect coloring the border of the area, for as long as it is selected
+pub fn highlight_selected_category(
+    color: Color,
+    area: Rect,
+) -> Effect {
+    fx::effect_fn((), u32::MAX, move |_, _, cells| {
+        cells.for_each(|(_, cell)| { cell.set_fg(color); });
+    })
+        .with_filter(CellFilter::Outer(Margin::new(1, 1)))
+        .with_area(area)
+}
+
 /// Animates the opening of all category widgets with staggered timing.
 ///
 /// # Arguments
 /// * `sender` - Channel for dispatching [ExabindEvent]s
 /// * `widgets` - Slice of [ShortcutsWidget]s to animate
+/// * `rng` - Source of the randomized delays
+/// * `overrides` - May replace the effect of each opening category
 ///
 /// # Returns
 /// An Effect that:
@@ -89,14 +110,19 @@ pub fn selected_category(
 /// 3. Triggers category selection
 pub fn open_all_categories(
     sender: Sender<ExabindEvent>,
-    widgets: &[ShortcutsWidget]
+    widgets: &[ShortcutsWidget],
+    rng: &mut SimpleRng,
+    overrides: &EffectOverrides,
 ) -> Effect {
-    let mut rng = SimpleRng::default();
 
     let max_open_category_delay = 150 * widgets.len() as u32;
     let open_categories_fx = widgets.iter().map(|w| {
         let delay = Duration::from_millis(rng.gen_range(0..max_open_category_delay));
-        prolong_start(delay, open_category(w.bg_color(), w.area()))
+        let open = overrides.effect(EffectEvent::CategoryOpened, w.bg_color())
+            .map(|fx| fx.with_area(w.area()))
+            .unwrap_or_else(|| open_category(w.bg_color(), w.area(), rng));
+
+        prolong_start(delay, open)
     }).collect::<Vec<_>>();
 
     sequence(&[
@@ -111,6 +137,7 @@ pub fn open_all_categories(
 /// # Arguments
 /// * `bg_color` - Background color for the category
 /// * `area` - Rectangular area of the category widget
+/// * `rng` - Seeds the randomness of the sweep and coalesce effects
 ///
 /// # Returns
 /// A parallel Effect combining:
@@ -120,6 +147,7 @@ pub fn open_all_categories(
 pub fn open_category(
     bg_color: Color,
     area: Rect,
+    rng: &mut SimpleRng,
 ) -> Effect {
     use tachyonfx::{fx::*, Interpolation::*};
 
@@ -131,12 +159,12 @@ pub fn open_category(
     let content_cells = CellFilter::Inner(Margin::new(1, 1));
 
     parallel(&[
-        prolong_start(timer, sweep_in(UpToDown, area.height, 0, bg_color, timer))
+        prolong_start(timer, sweep_in(UpToDown, area.height, 0, bg_color, timer).with_rng(SimpleRng::new(rng.gen())))
             .with_filter(content_cells.clone()),
-        prolong_start(timer, coalesce(timer_c))
+        prolong_start(timer, coalesce(timer_c).with_rng(SimpleRng::new(rng.gen())))
             .with_filter(border_cells),
         // plays out first, but must come last to not be overridden by the above effects
-        slide_in(UpToDown, area.height * 2, 0, CATPPUCCIN.crust, timer),
+        slide_in(UpToDown, area.height * 2, 0, theme().background(), timer).with_rng(SimpleRng::new(rng.gen())),
     ]).with_area(area)
 }
 
@@ -160,7 +188,7 @@ pub fn key_press<C: Into<Color>>(
     let key_borders = CellFilter::Outer(Margin::new(1, 1));
 
     let c = color.into();
-    let bg = Catppuccin::new().crust;
+    let bg = theme().background();
 
     parallel(&[
         // redraw singular border around key
@@ -182,15 +210,35 @@ pub fn key_press<C: Into<Color>>(
 /// Types out "exabind" with randomized delays between characters,
 /// followed by an Enter key press and persistent keyboard LED effects.
 ///
+/// # Arguments
+/// * `rng` - Source of the randomized delays
+/// * `led_border` - Whether to keep the LED animation going afterwards
+/// * `overrides` - May replace the whole sequence, or each key press
+///
 /// # Returns
-/// A never-ending Effect combining the startup sequence and LED animations.
-pub fn starting_up() -> Effect {
+/// An Effect combining the startup sequence and, if enabled, the never-ending
+/// LED animation.
+pub fn starting_up(rng: &mut SimpleRng, led_border: bool, overrides: &EffectOverrides) -> Effect {
+    if let Some(startup) = overrides.effect(EffectEvent::Startup, theme().kbd_key_press_color()) {
+        return match led_border {
+            true  => sequence(&[startup, fx::never_complete(led_kbd_border())]),
+            false => startup,
+        };
+    }
+
+    let press = |delay: Duration, key: KeyCap| {
+        let color = theme().kbd_key_press_color();
+        match overrides.effect(EffectEvent::KeyPressed, color) {
+            Some(fx) => fx::delay(delay, fx.with_area(key.area)),
+            None     => key_press(delay, key, color),
+        }
+    };
+
     let kbd = AnsiKeyboardTklLayout;
     let esc_area = kbd.key_area(KeyCode::Enter);
 
     let mut effects = vec![];
 
-    let mut rng = SimpleRng::default();
     let initial_delay = Duration::from_millis(300);
     let mut accrued_delay = initial_delay.as_millis();
 
@@ -198,22 +246,21 @@ pub fn starting_up() -> Effect {
         let delta: u32 = rng.gen_range(100..200);
         accrued_delay += delta;
 
-        let e = key_press(Duration::from_millis(accrued_delay), kbd.key_cap(c), Theme.kbd_key_press_color());
+        let e = press(Duration::from_millis(accrued_delay), kbd.key_cap(c));
         effects.push(e);
     });
 
     accrued_delay += 300;
-    let e = key_press(
-        Duration::from_millis(accrued_delay),
-        KeyCap::new(KeyCode::Enter, esc_area),
-        Theme.kbd_key_press_color()
-    );
+    let e = press(Duration::from_millis(accrued_delay), KeyCap::new(KeyCode::Enter, esc_area));
     effects.push(e);
 
-    effects.push(fx::delay(accrued_delay + 200, fx::parallel(&[
-        fx::never_complete(led_kbd_border()),
-        fx::fade_from_fg(CATPPUCCIN.crust, (800, Interpolation::SineOut))
-    ])));
+    let fade_in_labels = fx::fade_from_fg(theme().background(), (800, Interpolation::SineOut));
+    let after_enter = if led_border {
+        fx::parallel(&[fx::never_complete(led_kbd_border()), fade_in_labels])
+    } else {
+        fade_in_labels
+    };
+    effects.push(fx::delay(accrued_delay + 200, after_enter));
 
     fx::parallel(&effects)
 }
@@ -240,13 +287,9 @@ pub fn color_cycle_fg<I>(
     use tachyonfx::fx::*;
 
     let duration = Duration::from_millis(u32::MAX);
-    effect_fn((colors, None), duration, move |(colors, started_at), _ctx, cell_iter| {
-        if started_at.is_none() {
-            *started_at = Some(Instant::now());
-        }
-
-        let elapsed = started_at.as_ref().unwrap().elapsed().as_millis().max(1);
-        let raw_color_idx = elapsed as u32 / step_duration;
+    effect_fn((colors, 0u32), duration, move |(colors, elapsed_ms), ctx, cell_iter| {
+        *elapsed_ms = elapsed_ms.saturating_add(ctx.last_tick.as_millis());
+        let raw_color_idx = *elapsed_ms / step_duration;
 
         let color = |pos: Position| -> Color {
             let idx = (raw_color_idx + (pos.x / 2 + pos.y * 3 / 2) as u32) as usize;
@@ -270,17 +313,29 @@ pub fn color_cycle_fg<I>(
 /// # Returns
 /// A persistent Effect that animates the keyboard border lights.
 pub fn led_kbd_border() -> Effect {
-    let [color_1, color_2, color_3] = Theme.kbd_led_colors();
+    let [color_1, color_2, color_3] = theme().kbd_led_colors();
+
+    let is_lit = |cell: &Cell| {
+        let symbol = cell.symbol();
+        symbol != " " && !symbol.chars().next().map(is_box_drawing).unwrap_or(false)
+    };
+
+    if theme().simple_effects() {
+        let color_cycle = RepeatingColorCycle::bands(&[
+            (20, color_1),
+            (20, color_2),
+            (20, color_3),
+        ]);
+
+        return color_cycle_fg(color_cycle, 100, is_lit);
+    }
 
     let color_cycle = PingPongColorCycle::new(color_1, &[
         (40, color_2),
         (20, color_3),
     ]);
 
-    color_cycle_fg(color_cycle, 100, |cell| {
-        let symbol = cell.symbol();
-        symbol != " " && !symbol.chars().next().map(is_box_drawing).unwrap_or(false)
-    })
+    color_cycle_fg(color_cycle, 100, is_lit)
 }
 
 /// Creates an effect highlighting keyboard keys relevant to the selected category.
@@ -289,6 +344,8 @@ pub fn led_kbd_border() -> Effect {
 /// * `stage` - Effect stage for managing the animation
 /// * `context` - Current keymap context
 /// * `buffer_size` - Size of the rendering buffer
+/// * `rng` - Seeds the randomness of the sweep effect
+/// * `animated` - Whether to sweep in and color cycle the outlines
 ///
 /// # Returns
 /// A unique Effect that outlines and animates relevant key caps.
@@ -296,12 +353,16 @@ pub fn outline_selected_category_key_caps(
     stage: &mut EffectManager<UniqueEffectId>,
     context: &KeyMapContext,
     buffer_size: Size,
+    rng: &mut SimpleRng,
+    animated: bool,
 ) -> Effect {
     let buf = Buffer::empty(Rect::from((Position::default(), buffer_size)));
     let outline = KeyCapOutline::new(buf, context).into_effect();
+    if !animated {
+        return stage.unique(UniqueEffectId::KeyCapOutline, outline);
+    }
 
-    let color = Theme.kbd_cap_outline_category(context.sorted_category_idx()
-        .expect("selected category"))
+    let color = context.selected_category_outline()
         .fg
         .expect("fg color");
 
@@ -310,7 +371,8 @@ pub fn outline_selected_category_key_caps(
     let fx = parallel(&[
         outline,
         sequence(&[
-            sweep_in(UpToDown, 40, 40, CATPPUCCIN.crust, (350, Interpolation::QuadIn)),
+            sweep_in(UpToDown, 40, 40, theme().background(), (350, Interpolation::QuadIn))
+                .with_rng(SimpleRng::new(rng.gen())),
             color_cycle_fg(select_category_color_cycle(color, 9), 33, |_| true),
         ]).with_filter(keycap_outline),
     ]);
@@ -321,7 +383,7 @@ pub fn outline_selected_category_key_caps(
 
 fn draw_single_border(key_cap: KeyCap, duration: Duration) -> Effect {
     use tachyonfx::fx::*;
-    let border_style = Style::default().fg(Catppuccin::new().base);
+    let border_style = theme().kbd_key_press_border();
 
     effect_fn_buf((), duration, move |_state, _ctx, buf| {
         render_border_with(&[key_cap.clone()], buf, move |d, _pos, cell| {
@@ -344,7 +406,9 @@ fn is_box_drawing(c: char) -> bool {
     ('\u{2500}'..='\u{257F}').contains(&c)
 }
 
-/// Creates a repeating color cycle based on a base color.
+/// Creates a repeating color cycle based on a base color. Themes limited
+/// to the terminal's colours alternate the base color with the label color
+/// instead of shades derived from the base color.
 ///
 /// # Arguments
 /// * `base_color` - Primary color to derive the cycle from
@@ -356,6 +420,14 @@ fn select_category_color_cycle(
     base_color: Color,
     length_multiplier: usize
 ) -> ColorCycle<RepeatingCycle> {
+    if theme().simple_effects() {
+        let highlight = theme().shortcuts_widget_label().fg.unwrap_or(Color::Reset);
+        return RepeatingColorCycle::bands(&[
+            (4 * length_multiplier, base_color),
+            (4 * length_multiplier, highlight),
+        ]);
+    }
+
     let color_step: usize = 7 * length_multiplier;
 
     let (h, s, l) = color_to_hsl(&base_color);
@@ -374,3 +446,25 @@ fn select_category_color_cycle(
     ])
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::assert_snapshot;
+    use ratatui::widgets::{Block, Widget};
+
+    #[test]
+    fn test_selected_category() {
+        let area = Rect::new(0, 0, 24, 6);
+        let mut buf = Buffer::empty(area);
+        let mut effect = selected_category(theme().active_modifier, area);
+
+        // snapshots at 0, 0.5 and 2 seconds
+        for (name, tick) in [("0ms", 0), ("500ms", 500), ("2000ms", 1500)] {
+            Block::bordered().render(area, &mut buf);
+            effect.process(Duration::from_millis(tick), &mut buf, area);
+
+            assert_snapshot(&format!("selected_category_{name}"), &buf);
+        }
+    }
+}
@@ -4,8 +4,7 @@ use ratatui::layout::{Offset, Position, Rect};
 use ratatui::style::Style;
 use tachyonfx::{blit_buffer, CellFilter, Duration, Effect, Shader};
 use crate::app::{BoundShortcut, KeyMapContext};
-use crate::styling::{ExabindTheme, Theme};
-use crate::widget::{draw_key_border, render_border_with, supplant_key_code, AnsiKeyboardTklLayout, KeyCap, KeyboardLayout};
+use crate::widget::{draw_key_border, render_border_with, shortcut_key_caps, AnsiKeyboardTklLayout, KeyCap};
 
 #[derive(Clone, Debug)]
 pub struct KeyCapOutline {
@@ -18,7 +17,7 @@ impl KeyCapOutline {
         buffer: Buffer,
         context: &KeyMapContext,
     ) -> Self {
-        let style = Theme.kbd_cap_outline_category(context.sorted_category_idx().expect("no category selected"));
+        let style = context.selected_category_outline();
         let mut buffer = buffer;
         Self::update_shortcuts_outline(&mut buffer, context.filtered_actions(), style);
 
@@ -65,30 +64,13 @@ impl KeyCapOutline {
         shortcuts: Vec<BoundShortcut>,
         style: Style,
     ) {
-        let key_caps = AnsiKeyboardTklLayout
-            .key_cap_lookup();
-
-        let keys_to_outline: Vec<KeyCap> = shortcuts
-            .iter()
+        let shortcuts = shortcuts.iter()
             .filter(|action| action.enabled_in_ui())
-            .map(|action| action.shortcut())
-            .flat_map(|shortcut| shortcut.keystroke())
-            .filter_map(|key_code| key_caps.get(&supplant_key_code(*key_code))).cloned()
-            .collect();
-
+            .map(BoundShortcut::shortcut);
 
-        let mut key_caps: Vec<KeyCap> = keys_to_outline.to_vec();
+        let key_caps = shortcut_key_caps(&AnsiKeyboardTklLayout, shortcuts);
 
         let area = buf.area;
-        let keycap_cmp = |key_cap: &KeyCap| -> u32 {
-            let a = key_cap.area;
-            let width = area.width as u32;
-            a.x as u32 + (a.y as u32 * width)
-        };
-
-        key_caps.sort_by_key(keycap_cmp);
-        key_caps.dedup();
-
         outline_key_cap_borders(&key_caps, style)
             .process(Duration::from_millis(17), buf, area);
     }
@@ -1,3 +1,4 @@
 pub mod effect;
+pub mod overrides;
+pub mod policy;
 mod key_cap_outline;
-
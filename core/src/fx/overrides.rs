@@ -0,0 +1,159 @@
+use crate::styling::{theme, ExabindTheme};
+use ratatui::style::Color;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tachyonfx::dsl::EffectDsl;
+use tachyonfx::Effect;
+use toml::Table;
+
+/// Events whose effects can be replaced by a tachyonfx DSL expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EffectEvent {
+    /// Typing "exabind" on the keyboard, applied to the keyboard widget.
+    Startup,
+    /// The border of the selected category; runs until another category is selected.
+    CategorySelected,
+    /// A key pressed during startup, applied to the key cap.
+    KeyPressed,
+    /// A category widget opening, during startup.
+    CategoryOpened,
+}
+
+impl EffectEvent {
+    pub const ALL: &'static [EffectEvent] = &[
+        EffectEvent::Startup,
+        EffectEvent::CategorySelected,
+        EffectEvent::KeyPressed,
+        EffectEvent::CategoryOpened,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EffectEvent::Startup          => "startup",
+            EffectEvent::CategorySelected => "category-selected",
+            EffectEvent::KeyPressed       => "key-pressed",
+            EffectEvent::CategoryOpened   => "category-opened",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|event| event.name() == name)
+    }
+}
+
+/// Effects replacing the built-in ones, as tachyonfx DSL expressions keyed by
+/// [EffectEvent]. Expressions can refer to `color`, the color of the category
+/// or key press, and `bg`, the theme's background color.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectOverrides {
+    expressions: HashMap<EffectEvent, String>,
+    /// Errors of the expressions that failed to compile while running.
+    failures: RefCell<HashMap<EffectEvent, String>>,
+}
+
+impl EffectOverrides {
+    /// Reads the `[effects]` table of the config file; every expression is
+    /// compiled up front, so that mistakes are reported at startup.
+    pub fn from_table(table: &Table) -> Result<Self, String> {
+        let expressions = table.iter()
+            .map(|(key, value)| {
+                let event = EffectEvent::from_name(key)
+                    .ok_or(format!("unknown effect event {key}"))?;
+                let expression = value.as_str()
+                    .ok_or(format!("effects.{key} must be a string"))?;
+
+                // the theme isn't selected yet
+                compile(expression, Color::Reset, Color::Reset)
+                    .map_err(|e| format!("effects.{key}: {e}"))?;
+
+                Ok((event, expression.to_string()))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { expressions, failures: RefCell::default() })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expressions.is_empty()
+    }
+
+    /// The effect replacing the built-in one of the event, if any.
+    ///
+    /// Expressions are checked at startup, but with placeholder colors; one
+    /// that still fails to compile is recorded in [Self::failures], and the
+    /// event falls back to its built-in effect for the rest of the run.
+    pub fn effect(&self, event: EffectEvent, color: Color) -> Option<Effect> {
+        let expression = self.expressions.get(&event)?;
+        if self.failures.borrow().contains_key(&event) {
+            return None;
+        }
+
+        compile(expression, color, theme().background())
+            .map_err(|e| self.failures.borrow_mut().insert(event, e))
+            .ok()
+    }
+
+    /// The expressions that failed to compile while running, e.g. to report
+    /// them once the terminal is restored.
+    pub fn failures(&self) -> Vec<String> {
+        let failures = self.failures.borrow();
+        EffectEvent::ALL.iter()
+            .filter_map(|event| Some(format!("effects.{}: {}", event.name(), failures.get(event)?)))
+            .collect()
+    }
+}
+
+fn compile(expression: &str, color: Color, bg: Color) -> Result<Effect, String> {
+    EffectDsl::new().compiler()
+        .bind("color", color)
+        .bind("bg", bg)
+        .compile(expression)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(toml: &str) -> Result<EffectOverrides, String> {
+        EffectOverrides::from_table(&toml.parse::<Table>().unwrap())
+    }
+
+    #[test]
+    fn test_from_table() {
+        let overrides = overrides(r#"
+            category-opened = "fx::coalesce((500, ExpoOut))"
+            key-pressed = "fx::fade_from(color, bg, (300, SineOut))"
+        "#).unwrap();
+
+        assert!(overrides.effect(EffectEvent::CategoryOpened, Color::Red).is_some());
+        assert!(overrides.effect(EffectEvent::KeyPressed, Color::Red).is_some());
+        assert!(overrides.effect(EffectEvent::Startup, Color::Red).is_none());
+        assert_eq!(overrides.failures(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_runtime_failures_are_recorded() {
+        let overrides = EffectOverrides {
+            expressions: HashMap::from([(EffectEvent::KeyPressed, "fx::fade_from(color, fg, 300)".to_string())]),
+            failures: RefCell::default(),
+        };
+
+        assert!(overrides.effect(EffectEvent::KeyPressed, Color::Red).is_none());
+        assert!(overrides.effect(EffectEvent::KeyPressed, Color::Red).is_none());
+
+        let failures = overrides.failures();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("effects.key-pressed: "), "{}", failures[0]);
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        assert_eq!(overrides(r#"key-press = "fx::dissolve(500)""#),
+            Err("unknown effect event key-press".to_string()));
+        assert_eq!(overrides("startup = 500"),
+            Err("effects.startup must be a string".to_string()));
+        assert!(overrides(r#"startup = "fx::dissolve(""#).unwrap_err().starts_with("effects.startup: "));
+        assert!(overrides(r#"startup = "fx::no_such_effect(500)""#).is_err());
+    }
+}
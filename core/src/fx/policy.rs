@@ -0,0 +1,27 @@
+/// Which effects the app plays; consulted before effects are added to the
+/// app's and the keyboard's effect managers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectPolicy {
+    /// Replaces animations with instant changes.
+    pub reduced_motion: bool,
+    /// Cycles the key labels through the LED colours, for as long as exabind runs.
+    pub led_border: bool,
+}
+
+impl EffectPolicy {
+    /// Whether to play the startup, category and key cap animations.
+    pub fn animations(&self) -> bool {
+        !self.reduced_motion
+    }
+
+    /// Whether to light up the keyboard with [led_kbd_border](crate::fx::effect::led_kbd_border).
+    pub fn led_border(&self) -> bool {
+        self.led_border && !self.reduced_motion
+    }
+}
+
+impl Default for EffectPolicy {
+    fn default() -> Self {
+        Self { reduced_motion: false, led_border: true }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::app::ExabindApp;
+use crate::clock::FixedClock;
+use crate::dispatcher::Dispatcher;
+use crate::exabind_event::ExabindEvent;
+use crate::fx::overrides::EffectOverrides;
+use crate::fx::policy::EffectPolicy;
+use crate::keymap::KeyMap;
+use crate::ui::{effects, ui};
+use crate::ui_state::UiState;
+use crate::widget::AnsiKeyboardTklLayout;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Size;
+use ratatui::Terminal;
+use std::sync::mpsc::{self, Receiver};
+use tachyonfx::Duration;
+
+/// Seed of the effects' randomness, so that every run renders the same frames.
+const SEED: u32 = 0xeab1d;
+
+/// Runs the app as the TUI does, but renders into an in-memory buffer, with
+/// a [FixedClock] advancing by `frame_duration` per frame. Given the same
+/// keymap and events, the frames are the same on every run.
+pub struct Headless {
+    app: ExabindApp,
+    ui_state: UiState,
+    events: Receiver<ExabindEvent>,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Headless {
+    /// Starts up the app with the keymap, on a screen of the given size.
+    pub fn new(keymap: KeyMap, size: Size, frame_duration: Duration) -> Self {
+        Self::with_effects(keymap, size, frame_duration, EffectPolicy::default(), EffectOverrides::default())
+    }
+
+    /// Like [Headless::new], with the effects gated by the policy and the
+    /// built-in ones replaced by the overrides.
+    pub fn with_effects(
+        keymap: KeyMap,
+        size: Size,
+        frame_duration: Duration,
+        effect_policy: EffectPolicy,
+        effect_overrides: EffectOverrides,
+    ) -> Self {
+        let (sender, events) = mpsc::channel();
+
+        let mut ui_state = UiState::new();
+        ui_state.screen = size;
+        let mut app = ExabindApp::new(&mut ui_state, sender, keymap)
+            .with_clock(FixedClock::new(frame_duration))
+            .with_seed(SEED)
+            .with_effect_policy(effect_policy)
+            .with_effect_overrides(effect_overrides);
+
+        ui_state.reset_kbd_buffer(AnsiKeyboardTklLayout);
+        app.start_up(&mut ui_state);
+
+        let terminal = Terminal::new(TestBackend::new(size.width, size.height))
+            .expect("test backend to be infallible");
+
+        Self { app, ui_state, events, terminal }
+    }
+
+    pub fn app(&self) -> &ExabindApp {
+        &self.app
+    }
+
+    /// Applies pending events, advances the clock and renders the next frame.
+    pub fn next_frame(&mut self) -> &Buffer {
+        let elapsed = self.app.update_time();
+        while let Ok(event) = self.events.try_recv() {
+            self.app.apply_event(event, &mut self.ui_state);
+        }
+
+        let (app, ui_state) = (&mut self.app, &mut self.ui_state);
+        self.terminal.draw(|f| {
+            ui_state.apply_kbd_effects(elapsed);
+            ui(f, app.stateful_widgets(), ui_state);
+            effects(elapsed, app, f);
+        }).expect("test backend to be infallible");
+
+        self.terminal.backend().buffer()
+    }
+
+    /// Renders the next `n` frames.
+    pub fn frames(&mut self, n: usize) -> Vec<Buffer> {
+        (0..n).map(|_| self.next_frame().clone()).collect()
+    }
+}
+
+impl Dispatcher<ExabindEvent> for Headless {
+    fn dispatch(&self, event: ExabindEvent) {
+        self.app.dispatch(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::kde_keymap;
+    use crate::snapshot::{assert_snapshot, find_glyphs, FALLBACK_GLYPHS};
+
+    fn headless() -> Headless {
+        Headless::new(kde_keymap(), Size::new(120, 30), Duration::from_millis(50))
+    }
+
+    #[test]
+    fn test_frames_are_deterministic() {
+        let frames = headless().frames(80);
+        assert_eq!(frames, headless().frames(80));
+
+        // typing "exabind" a second in
+        assert_ne!(frames[20], frames[21]);
+    }
+
+    #[test]
+    fn test_category_is_selected_after_startup() {
+        let mut headless = headless();
+        assert_eq!(headless.app().keymap_context().category(), None);
+
+        headless.frames(80);
+        assert!(headless.app().keymap_context().category().is_some());
+    }
+
+    #[test]
+    fn test_reduced_motion() {
+        let policy = EffectPolicy { reduced_motion: true, ..EffectPolicy::default() };
+        let mut headless = Headless::with_effects(kde_keymap(), Size::new(120, 30), Duration::from_millis(50), policy, EffectOverrides::default());
+
+        let frames = headless.frames(3);
+        assert!(headless.app().keymap_context().category().is_some());
+
+        // no startup animation or colour cycling
+        assert_eq!(frames[1], frames[2]);
+        assert_eq!(&frames[2], headless.next_frame());
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let frames = headless().frames(81);
+
+        // typing "exabind", and the first category selected
+        assert_snapshot("headless_1000ms", &frames[20]);
+        assert_snapshot("headless_4000ms", &frames[80]);
+
+        for frame in &frames {
+            assert_eq!(find_glyphs(frame, FALLBACK_GLYPHS), vec![]);
+        }
+    }
+}
@@ -1,23 +1,113 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::io::Read;
 use std::path::PathBuf;
-use crate::shortcut::Action;
+use crate::parser::KeymapFormat;
+use crate::shortcut::{Action, Shortcut};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyMap {
     name: String,
     actions: HashMap<String, Vec<Action>>,
+    global_sources: Vec<String>,
+    /// Actions without shortcuts of their own, but with default shortcuts;
+    /// not part of any category.
+    #[cfg_attr(feature = "serde", serde(default))]
+    unbound: Vec<Action>,
+    /// Whether [SHADOWED_CATEGORY] was added by [KeyMap::with_shadowed_category],
+    /// rather than read from a keymap file.
+    #[cfg_attr(feature = "serde", serde(default))]
+    shadowed_category: bool,
 }
 
-
+/// A shortcut of a global keymap (e.g. KDE or niri) whose keystroke is also
+/// bound by an application; the application never receives the keystroke.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowedShortcut {
+    pub shortcut: Shortcut,
+    pub winner: Action,
+    pub loser: Action,
+}
 
 static EMPTY_ACTIONS: Vec<Action> = Vec::new();
 
+/// A shortcut bound to more than one action at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub shortcut: Shortcut,
+    pub actions: Vec<Action>,
+}
+
+/// Category of the actions added by [KeyMap::with_shadowed_category].
+pub const SHADOWED_CATEGORY: &str = "shadowed";
+
 impl KeyMap {
 
-    pub fn new<S: ToString>(name: S, actions: HashMap<String, Vec<Action>>) -> Self {
-        Self { name: name.to_string(), actions }
+    pub fn new<S: ToString>(name: S, mut actions: HashMap<String, Vec<Action>>) -> Self {
+        let name = name.to_string();
+        actions.values_mut()
+            .flatten()
+            .for_each(|action| action.update_source(&name));
+
+        Self { name, actions, global_sources: Vec::new(), unbound: Vec::new(), shadowed_category: false }
+    }
+
+    /// Adds actions that are unbound, but have default shortcuts, e.g. KDE
+    /// shortcuts the user has cleared; they are only [exported](crate::export).
+    pub fn with_unbound_actions(mut self, mut actions: Vec<Action>) -> Self {
+        actions.iter_mut()
+            .for_each(|action| action.update_source(&self.name));
+
+        self.unbound.extend(actions);
+        self
+    }
+
+    /// Marks the keymap as global, i.e. its shortcuts are grabbed by the
+    /// desktop before any application sees them.
+    pub fn global(mut self) -> Self {
+        self.global_sources = vec![self.name.clone()];
+        self
+    }
+
+    /// Merges several keymaps into one. When there is more than one keymap,
+    /// each category is prefixed by the name of the keymap it came from, e.g.
+    /// "kitty: tabs"; the actions keep track of their [source](Action::source).
+    pub fn merge(keymaps: Vec<KeyMap>) -> Self {
+        if keymaps.len() == 1 {
+            return keymaps.into_iter().next().unwrap();
+        }
+
+        let name = keymaps.iter()
+            .map(|keymap| keymap.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + ");
+
+        let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+        let mut global_sources = Vec::new();
+        let mut unbound = Vec::new();
+        for keymap in keymaps {
+            global_sources.extend(keymap.global_sources);
+            unbound.extend(keymap.unbound.into_iter().map(|mut action| {
+                action.update_category(format!("{}: {}", keymap.name, action.category()));
+                action
+            }));
+            for (category, mut category_actions) in keymap.actions {
+                let category = format!("{}: {}", keymap.name, category);
+                category_actions.iter_mut()
+                    .for_each(|action| action.update_category(&category));
+
+                actions.entry(category)
+                    .or_default()
+                    .extend(category_actions);
+            }
+        }
+
+        Self { name, actions, global_sources, unbound, shadowed_category: false }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     pub fn actions_by_category(&self, category: &str) -> &[Action] {
@@ -29,13 +119,130 @@ impl KeyMap {
         self.actions.values().flat_map(|v| v.iter())
     }
 
+    /// See [Self::with_unbound_actions].
+    pub fn unbound_actions(&self) -> &[Action] {
+        &self.unbound
+    }
+
+    /// Whether the action belongs to a [global](Self::global) keymap.
+    pub fn is_global(&self, action: &Action) -> bool {
+        self.global_sources.iter().any(|source| source == action.source())
+    }
+
+    /// All application shortcuts shadowed by a global shortcut. A global
+    /// shortcut grabs its first keystroke, so any application shortcut
+    /// starting with the same keystroke is shadowed.
+    pub fn shadowed_shortcuts(&self) -> Vec<ShadowedShortcut> {
+        let (global, application): (Vec<&Action>, Vec<&Action>) = self.keymap_actions()
+            .partition(|action| self.is_global(action));
+
+        let global = bound(global);
+        bound(application).into_iter()
+            .flat_map(|(shortcut, loser)| {
+                global.iter()
+                    .filter(|(grabbed, _)| grabbed.keystroke() == shortcut.keystroke())
+                    .map(move |(_, winner)| ShadowedShortcut {
+                        shortcut: shortcut.clone(),
+                        winner: (*winner).clone(),
+                        loser: loser.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// All shortcuts bound to more than one action. Within an application,
+    /// only actions of the same category (usually a mode or context) conflict;
+    /// the shortcuts of a global keymap are all active at once, so its actions
+    /// conflict across categories. See [Self::shadowed_shortcuts] for conflicts
+    /// between keymaps.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut bound: BTreeMap<(&str, String), (&Shortcut, Vec<&Action>)> = BTreeMap::new();
+        for action in self.keymap_actions() {
+            let scope = match self.is_global(action) {
+                true  => action.source(),
+                false => action.category(),
+            };
+
+            for shortcut in action.shortcuts() {
+                let (_, actions) = bound.entry((scope, shortcut.to_string()))
+                    .or_insert_with(|| (shortcut, Vec::new()));
+
+                if actions.iter().all(|a| a.name() != action.name()) {
+                    actions.push(action);
+                }
+            }
+        }
+
+        bound.into_values()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|(shortcut, actions)| Conflict {
+                shortcut: shortcut.clone(),
+                actions: actions.into_iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    /// The actions read from the keymap files, i.e. without those of the
+    /// [shadowed category](Self::with_shadowed_category).
+    fn keymap_actions(&self) -> impl Iterator<Item=&Action> {
+        self.actions()
+            .filter(|action| !(self.shadowed_category && action.category() == SHADOWED_CATEGORY))
+    }
+
+    /// Adds a category pairing each shadowed application shortcut with the
+    /// global action that shadows it, if there are any. The category is left
+    /// out of [Self::shadowed_shortcuts] and [Self::conflicts].
+    pub fn with_shadowed_category(mut self) -> Self {
+        let actions: Vec<Action> = self.shadowed_shortcuts()
+            .into_iter()
+            .map(|shadowed| {
+                let label = format!(
+                    "{}: {} ⟵ {}: {}",
+                    shadowed.winner.source(),
+                    shadowed.winner.name(),
+                    shadowed.loser.source(),
+                    shadowed.loser.name(),
+                );
+                let mut action = Action::new_filter_empty(label, SHADOWED_CATEGORY.to_string(), vec![shadowed.shortcut]);
+                action.update_source(&self.name);
+                action
+            })
+            .collect();
+
+        if !actions.is_empty() {
+            self.actions.insert(SHADOWED_CATEGORY.to_string(), actions);
+            self.shadowed_category = true;
+        }
+
+        self
+    }
+
+    /// The names of all categories, sorted.
+    pub fn category_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.actions.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    /// The categories and their number of actions, sorted by name; the order
+    /// determines the colours of the categories.
     pub fn categories(&self) -> Vec<(String, usize)> {
-        self.actions.iter()
+        let mut categories: Vec<(String, usize)> = self.actions.iter()
             .map(|(category, actions)| (category.clone(), actions.len()))
-            .collect()
+            .collect();
+
+        categories.sort();
+        categories
     }
 }
 
+/// Pairs each shortcut with the action it belongs to.
+fn bound(actions: Vec<&Action>) -> Vec<(&Shortcut, &Action)> {
+    actions.into_iter()
+        .flat_map(|action| action.shortcuts().iter().map(move |shortcut| (shortcut, action)))
+        .collect()
+}
+
 impl Display for KeyMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let format = |action: &Action| format!("\t{}", action);
@@ -46,23 +253,171 @@ impl Display for KeyMap {
 
 
 pub trait IntoKeyMap {
-    fn into_keymap<F: FnOnce(&str) -> KeyMap>(self, f: F) -> KeyMap;
+    fn into_keymap<F: FnOnce(&str) -> Result<KeyMap, String>>(self, f: F) -> Result<KeyMap, String>;
+
+    /// Parses into a [KeyMap] with the given [KeymapFormat].
+    fn into_keymap_as(self, format: &dyn KeymapFormat) -> Result<KeyMap, String>
+    where
+        Self: Sized,
+    {
+        self.into_keymap(|input| format.parse(input))
+    }
 }
 
 impl IntoKeyMap for &str {
-    fn into_keymap<F: FnOnce(&str) -> KeyMap>(self, f: F) -> KeyMap {
+    fn into_keymap<F: FnOnce(&str) -> Result<KeyMap, String>>(self, f: F) -> Result<KeyMap, String> {
         f(self)
     }
 }
 
 impl IntoKeyMap for PathBuf {
-    fn into_keymap<F: FnOnce(&str) -> KeyMap>(self, f: F) -> KeyMap {
+    fn into_keymap<F: FnOnce(&str) -> Result<KeyMap, String>>(self, f: F) -> Result<KeyMap, String> {
         let mut input = String::new();
-        let mut file = std::fs::File::open(self)
-            .expect("file to be present");
-        file.read_to_string(&mut input)
-            .expect("parsable xml");
+        std::fs::File::open(&self)
+            .and_then(|mut file| file.read_to_string(&mut input))
+            .map_err(|e| format!("Could not read {}: {e}", self.display()))?;
 
         f(&input)
+            .map_err(|e| format!("Could not parse {}: {e}", self.display()))
+    }
+}
+
+/// The KDE keymap shared by tests: Overview (Meta+W) in kwin and Activities
+/// (Meta+Q) in plasmashell; extend it with [KeyMap::with_actions].
+#[cfg(test)]
+pub(crate) fn kde_keymap() -> KeyMap {
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::LeftMeta;
+
+    let meta = |key| Shortcut::new(vec![Modifier(LeftMeta), key]);
+    KeyMap::new("KDE", HashMap::from([
+        ("kwin".to_string(), vec![Action::new_filter_empty("Overview", "kwin", vec![meta(Char('w'))])]),
+        ("plasmashell".to_string(), vec![Action::new_filter_empty("Activities", "plasmashell", vec![meta(Char('q'))])]),
+    ]))
+}
+
+#[cfg(test)]
+impl KeyMap {
+    /// Adds the actions to their categories.
+    pub(crate) fn with_actions(mut self, actions: Vec<Action>) -> Self {
+        for mut action in actions {
+            action.update_source(&self.name);
+            self.actions.entry(action.category().to_string()).or_default().push(action);
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+
+    fn keymap(name: &str, category: &str, action: &str) -> KeyMap {
+        let actions = vec![Action::new_filter_empty(action, category, vec![Shortcut::new(vec![Char('a')])])];
+        KeyMap::new(name, HashMap::from([(category.to_string(), actions)]))
+    }
+
+    #[test]
+    fn test_into_keymap_error_names_path() {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../test/kglobalshortcutsrc"));
+        let error = path.clone().into_keymap(|_| Err("invalid".to_string())).unwrap_err();
+        assert_eq!(error, format!("Could not parse {}: invalid", path.display()));
+
+        let missing = PathBuf::from("no/such/kglobalshortcutsrc");
+        assert!(missing.into_keymap(|_| unreachable!()).unwrap_err()
+            .starts_with("Could not read no/such/kglobalshortcutsrc: "));
+    }
+
+    #[test]
+    fn test_merge() {
+        let keymap = KeyMap::merge(vec![
+            keymap("KDE", "kwin", "Overview"),
+            keymap("kitty", "tabs", "new_tab"),
+        ]);
+
+        assert_eq!(keymap.name(), "KDE + kitty");
+
+        assert_eq!(keymap.categories(), vec![
+            ("KDE: kwin".to_string(), 1),
+            ("kitty: tabs".to_string(), 1),
+        ]);
+
+        let actions = keymap.actions_by_category("kitty: tabs");
+        assert_eq!(actions[0].name(), "new_tab");
+        assert_eq!(actions[0].source(), "kitty");
+    }
+
+    #[test]
+    fn test_merge_single_keymap() {
+        let keymap = KeyMap::merge(vec![keymap("KDE", "kwin", "Overview")]);
+
+        assert_eq!(keymap.categories(), vec![("kwin".to_string(), 1)]);
+        assert_eq!(keymap.actions_by_category("kwin")[0].source(), "KDE");
+    }
+
+    #[test]
+    fn test_shadowed_shortcuts() {
+        let ctrl = |c| Shortcut::new(vec![Modifier(LeftControl), Char(c)]);
+        let kde = KeyMap::new("KDE", HashMap::from([("kwin".to_string(), vec![
+            Action::new_filter_empty("Overview", "kwin", vec![ctrl('w')]),
+        ])])).global();
+        let kitty = KeyMap::new("kitty", HashMap::from([("tabs".to_string(), vec![
+            Action::new_filter_empty("close_tab", "tabs", vec![ctrl('w')]),
+            Action::new_filter_empty("new_tab", "tabs", vec![ctrl('t')]),
+        ])]));
+        let emacs = KeyMap::new("Emacs", HashMap::from([("global".to_string(), vec![
+            Action::new_filter_empty("kill-region", "global", vec![
+                Shortcut::sequence(vec![vec![Modifier(LeftControl), Char('w')], vec![Char('x')]]),
+            ]),
+        ])]));
+
+        let keymap = KeyMap::merge(vec![kde, kitty, emacs]);
+        let mut shadowed: Vec<(String, String)> = keymap.shadowed_shortcuts().iter()
+            .map(|s| (s.winner.name().to_string(), s.loser.name().to_string()))
+            .collect();
+        shadowed.sort();
+        assert_eq!(shadowed, vec![
+            ("Overview".to_string(), "close_tab".to_string()),
+            ("Overview".to_string(), "kill-region".to_string()),
+        ]);
+
+        let keymap = keymap.with_shadowed_category();
+        let actions = keymap.actions_by_category(SHADOWED_CATEGORY);
+        assert_eq!(actions.len(), 2);
+        assert!(actions.iter().any(|a| a.name() == "KDE: Overview ⟵ kitty: close_tab"));
+
+        // the shadowed category is not an application's
+        assert_eq!(keymap.shadowed_shortcuts().len(), 2);
+        assert!(keymap.conflicts().is_empty());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let ctrl = |c| Shortcut::new(vec![Modifier(LeftControl), Char(c)]);
+        let kitty = KeyMap::new("kitty", HashMap::from([
+            ("tabs".to_string(), vec![
+                Action::new_filter_empty("close_tab", "tabs", vec![ctrl('w')]),
+                Action::new_filter_empty("close_window", "tabs", vec![ctrl('w')]),
+                Action::new_filter_empty("new_tab", "tabs", vec![ctrl('t')]),
+            ]),
+            ("scrollback".to_string(), vec![
+                Action::new_filter_empty("scroll_home", "scrollback", vec![ctrl('t')]),
+            ]),
+        ]));
+
+        let conflicts = kitty.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].shortcut, ctrl('w'));
+        assert_eq!(conflicts[0].actions.len(), 2);
+
+        let kde = KeyMap::new("KDE", HashMap::from([
+            ("kwin".to_string(), vec![Action::new_filter_empty("Overview", "kwin", vec![ctrl('t')])]),
+            ("plasmashell".to_string(), vec![Action::new_filter_empty("Activities", "plasmashell", vec![ctrl('t')])]),
+        ])).global();
+
+        assert_eq!(kde.conflicts().len(), 1);
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,20 @@
 pub mod shortcut;
 pub mod keymap;
+pub mod diff;
+#[cfg(feature = "serde")]
+pub mod export;
+pub mod report;
+pub mod cheat_sheet;
+pub mod svg;
+pub mod ansi;
+pub mod headless;
+pub mod recording;
 pub mod parser;
 pub mod args;
 pub mod crossterm;
 pub mod app;
+pub mod clock;
+pub mod config;
 pub mod event_handler;
 pub mod fx;
 pub mod stateful_widgets;
@@ -11,12 +22,15 @@ pub mod styling;
 #[cfg(feature = "crossterm")]
 pub mod tui;
 pub mod widget;
+pub mod ui;
 pub mod ui_state;
 pub mod dispatcher;
 pub mod exabind_event;
 pub mod input;
 pub mod color_cycle;
 pub mod key_event;
+#[cfg(test)]
+mod snapshot;
 
 pub use shortcut::{Action, Shortcut};
 pub use keymap::{KeyMap, IntoKeyMap};
@@ -0,0 +1,179 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+use toml::{Table, Value};
+
+const DEFAULT_CATEGORY: &str = "Alacritty";
+
+/// Parses the `[keyboard] bindings` of an `alacritty.toml` into a [KeyMap].
+///
+/// Bindings restricted to a terminal mode (`mode = "Vi|~Search"`) are
+/// grouped by that mode; all other bindings share a single category.
+pub fn parse_alacritty_toml(input: &str) -> Result<KeyMap, String> {
+    let config: Table = input.parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+
+    let bindings = config.get("keyboard")
+        .and_then(|keyboard| keyboard.get("bindings"))
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    bindings.iter()
+        .filter_map(Value::as_table)
+        .filter_map(binding)
+        .for_each(|(category, action)| actions.entry(category).or_default().push(action));
+
+    Ok(KeyMap::new("Alacritty", actions))
+}
+
+pub struct AlacrittyFormat;
+
+impl KeymapFormat for AlacrittyFormat {
+    fn name(&self) -> &'static str {
+        "alacritty"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["alacritty.toml", "alacritty/*.toml"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.contains("[keyboard]") || input.contains("[[keyboard.bindings]]")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_alacritty_toml(input)
+    }
+}
+
+fn binding(binding: &Table) -> Option<(String, Action)> {
+    let key = binding.get("key")?.as_str()?;
+    let mods = binding.get("mods").and_then(Value::as_str).unwrap_or("None");
+
+    let mut keystroke = mods.split('|')
+        .map(str::trim)
+        .filter(|m| *m != "None")
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+    keystroke.push(key_code(key)?);
+
+    let category = binding.get("mode")
+        .and_then(Value::as_str)
+        .map(mode_category)
+        .filter(|mode| !mode.is_empty())
+        .unwrap_or(DEFAULT_CATEGORY.to_string());
+
+    let label = action_label(binding)?;
+    let action = Action::new_filter_empty(label, category.clone(), vec![Shortcut::new(keystroke)]);
+    Some((category, action))
+}
+
+/// Describes what a binding does: an `action`, the `chars` it sends or the
+/// `command` it runs.
+fn action_label(binding: &Table) -> Option<String> {
+    if let Some(action) = binding.get("action").and_then(Value::as_str) {
+        return (action != "ReceiveChar" && action != "None").then(|| action.to_string());
+    }
+
+    if let Some(chars) = binding.get("chars").and_then(Value::as_str) {
+        return Some(format!("send {}", chars.escape_debug()));
+    }
+
+    match binding.get("command")? {
+        Value::String(program) => Some(program.clone()),
+        Value::Table(command)  => {
+            let program = command.get("program")?.as_str()?;
+            let args = command.get("args")
+                .and_then(Value::as_array)
+                .map(|args| args.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+                .unwrap_or_default();
+
+            Some(std::iter::once(program).chain(args).collect::<Vec<_>>().join(" "))
+        },
+        _ => None,
+    }
+}
+
+/// Names the category of a binding mode, ignoring negated modes: `Vi|~Search`
+/// becomes "Vi".
+fn mode_category(mode: &str) -> String {
+    mode.split('|')
+        .map(str::trim)
+        .filter(|m| !m.starts_with('~'))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    // winit key names, as used by alacritty
+    let key_code = match name {
+        "Back"         => KeyCode::Backspace,
+        "Equals"       => KeyCode::Char('='),
+        "LBracket"     => KeyCode::Char('['),
+        "RBracket"     => KeyCode::Char(']'),
+        "ArrowUp"      => KeyCode::Up,
+        "ArrowDown"    => KeyCode::Down,
+        "ArrowLeft"    => KeyCode::Left,
+        "ArrowRight"   => KeyCode::Right,
+        "NumpadEnter"  => KeyCode::Enter,
+        n if n.len() == 4 && n.starts_with("Key") => return key_code(&n[3..]),
+        n => match key_names::key_code(n)? {
+            // letters are case-insensitive; shift is part of `mods`
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            key_code         => key_code,
+        },
+    };
+
+    Some(key_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_alacritty_toml() {
+        let input = r#"
+[keyboard]
+bindings = [
+    { key = "N", mods = "Control|Shift", action = "CreateNewWindow" },
+    { key = "Key5", mods = "Alt", chars = "\u001b[15~" },
+    { key = "K", mods = "Command", mode = "~Vi|~Search", action = "ClearHistory" },
+    { key = "I", mode = "Vi|~Search", action = "ScrollToBottom" },
+    { key = "Return", mods = "Super", command = { program = "alacritty", args = ["-e", "htop"] } },
+    { key = "Copy", action = "Copy" },
+    { key = "L", mods = "Control", action = "ReceiveChar" },
+]
+"#;
+
+        let keymap = parse_alacritty_toml(input).unwrap();
+
+        assert_categories(&keymap, &[
+            ("Alacritty", 4),
+            ("Vi", 1),
+        ]);
+
+        let actions = keymap.actions_by_category("Alacritty");
+        assert_eq!(actions[0].name(), "CreateNewWindow");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftShift), Char('n'),
+        ])]);
+        assert_eq!(actions[1].name(), "send \\u{1b}[15~");
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Char('5')])]);
+        assert_eq!(actions[3].name(), "alacritty -e htop");
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Enter])]);
+    }
+
+    #[test]
+    fn test_invalid_alacritty_toml() {
+        let error = parse_alacritty_toml("[keyboard\nbindings = []").unwrap_err();
+        assert!(error.contains("line 1"), "{error}");
+    }
+}
@@ -0,0 +1,349 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+/// Parses the output of Emacs' `describe-bindings` (or a `\{keymap}` table
+/// expanded by `substitute-command-keys`) into a [KeyMap].
+///
+/// Every "... Bindings:" section becomes a category: minor modes are named
+/// after the mode, while the major mode and global sections become
+/// "Major Mode" and "Global". Key translation sections, prefix keymaps and
+/// mouse bindings are skipped.
+pub fn parse_emacs_bindings(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut category: Option<String> = None;
+    let mut pending_key: Option<&str> = None;
+
+    for line in input.lines() {
+        let line = line.trim_end_matches('\u{c}');
+        if line.trim().is_empty() || is_table_header(line) {
+            continue;
+        }
+
+        if let Some(heading) = section_heading(line) {
+            category = category_name(heading);
+            pending_key = None;
+            continue;
+        }
+
+        let Some(category) = category.as_ref() else { continue };
+
+        // long keys push the binding onto the next, indented, line
+        let (key, binding) = match (pending_key.take(), split_binding(line)) {
+            (Some(key), _) if line.starts_with(char::is_whitespace) => (key, line.trim()),
+            (_, Some((key, binding)))                                => (key, binding),
+            (_, None)                                                => {
+                pending_key = Some(line.trim());
+                continue;
+            }
+        };
+
+        if !is_command(binding) {
+            continue;
+        }
+
+        let shortcuts = parse_key_description(key);
+        if !shortcuts.is_empty() {
+            actions.entry(category.clone())
+                .or_default()
+                .push(Action::new_filter_empty(binding, category, shortcuts));
+        }
+    }
+
+    KeyMap::new("Emacs", actions)
+}
+
+pub struct EmacsFormat;
+
+impl KeymapFormat for EmacsFormat {
+    fn name(&self) -> &'static str {
+        "emacs"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        // the column headers of each section
+        input.lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .any(|words| words == ["key", "binding"])
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_emacs_bindings(input))
+    }
+}
+
+/// Parses a key description such as `C-x 4 f`, `M-<left>` or `C-0 .. C-9`.
+fn parse_key_description(key: &str) -> Vec<Shortcut> {
+    match key.split_once(" .. ") {
+        Some((first, last)) => key_range(first, last),
+        None => key_sequence(key).map(Shortcut::sequence).into_iter().collect(),
+    }
+}
+
+fn key_sequence(key: &str) -> Option<Vec<Vec<KeyCode>>> {
+    // `<remap>` entries rebind commands, not keys
+    if key.starts_with("<remap>") {
+        return None;
+    }
+
+    key.split_whitespace()
+        .map(keystroke)
+        .collect()
+}
+
+/// Expands ranges of plain characters, e.g. `C-0 .. C-9`. Larger ranges
+/// (typically `self-insert-command`) are skipped.
+fn key_range(first: &str, last: &str) -> Vec<Shortcut> {
+    const MAX_RANGE: u32 = 16;
+
+    let (Some(mut first), Some(mut last)) = (key_sequence(first), key_sequence(last)) else {
+        return Vec::new();
+    };
+
+    let (Some(mut from), Some(mut to)) = (first.pop(), last.pop()) else {
+        return Vec::new();
+    };
+
+    let (Some(KeyCode::Char(a)), Some(KeyCode::Char(b))) = (from.pop(), to.pop()) else {
+        return Vec::new();
+    };
+
+    if first != last || from != to || a > b || (b as u32 - a as u32) >= MAX_RANGE {
+        return Vec::new();
+    }
+
+    (a..=b)
+        .map(|c| {
+            let mut keystroke = from.clone();
+            keystroke.push(KeyCode::Char(c));
+
+            let mut keystrokes = first.clone();
+            keystrokes.push(keystroke);
+            Shortcut::sequence(keystrokes)
+        })
+        .collect()
+}
+
+/// Parses a single keystroke, e.g. `C-M-%`, `s-<return>` or `<C-left>`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    let mut keystroke = Vec::new();
+    let mut key = key;
+
+    while let Some((m, rest)) = modifier_prefix(key) {
+        keystroke.push(KeyCode::Modifier(m));
+        key = rest;
+    }
+
+    match key.strip_prefix('<').and_then(|k| k.strip_suffix('>')) {
+        // older emacs versions place the modifiers inside the brackets: <C-left>
+        Some(name) if modifier_prefix(name).is_some() => {
+            keystroke.extend(self::keystroke(name)?);
+        }
+        Some(name) => keystroke.push(named_key(name)?),
+        None       => keystroke.extend(key_names::unshifted(named_key(key)?)),
+    }
+
+    Some(keystroke)
+}
+
+fn modifier_prefix(key: &str) -> Option<(ModifierKeyCode, &str)> {
+    use ModifierKeyCode::*;
+
+    let rest = key.get(2..).filter(|rest| !rest.is_empty())?;
+    let modifier = match key.get(..2)? {
+        "C-" => LeftControl,
+        "M-" => LeftAlt,
+        "A-" => LeftAlt,
+        "s-" => LeftMeta,
+        "S-" => LeftShift,
+        "H-" => LeftHyper,
+        _    => return None,
+    };
+
+    Some((modifier, rest))
+}
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    match name {
+        // emacs' DEL is the backspace key
+        "DEL"           => Some(KeyCode::Backspace),
+        "LFD"           => Some(KeyCode::Enter),
+        n if n.contains("mouse") || n.contains("wheel") => None,
+        n               => key_names::key_code(n),
+    }
+}
+
+/// Whether the binding is a command, rather than e.g. a prefix keymap, which
+/// is shown either as "Prefix Command" or by name, e.g. `ctl-x-4-prefix`.
+fn is_command(binding: &str) -> bool {
+    !matches!(binding, "Prefix Command" | "??" | "undefined")
+        && !binding.ends_with("-prefix")
+        && !binding.starts_with("#<")
+        && !binding.starts_with("[")
+}
+
+fn is_table_header(line: &str) -> bool {
+    let mut columns = line.split_whitespace();
+    matches!(
+        (columns.next(), columns.next(), columns.next()),
+        (Some("key"), Some("binding"), None) | (Some("---"), Some("-------"), None)
+    )
+}
+
+/// Splits a binding line into the key and the bound command; the columns are
+/// separated by tabs, or by at least two spaces.
+fn split_binding(line: &str) -> Option<(&str, &str)> {
+    let (key, binding) = line.split_once('\t')
+        .or_else(|| line.split_once("  "))?;
+
+    let (key, binding) = (key.trim(), binding.trim());
+    (!key.is_empty() && !binding.is_empty()).then_some((key, binding))
+}
+
+fn section_heading(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) || line.contains('\t') {
+        return None;
+    }
+
+    line.strip_suffix(':')
+        .filter(|heading| heading.contains("Bindings") || heading.contains("translations"))
+}
+
+/// Resolves the category of a section heading; sections that don't bind
+/// commands (key translations) resolve to `None`.
+fn category_name(heading: &str) -> Option<String> {
+    if heading.contains("translations") {
+        return None;
+    }
+
+    let quoted = |open: char, close: char| -> Option<&str> {
+        let (_, rest) = heading.split_once(open)?;
+        rest.split_once(close).map(|(name, _)| name)
+    };
+
+    let name = quoted('`', '\'')
+        .or_else(|| quoted('‘', '’'))
+        .map(str::to_string)
+        .unwrap_or_else(|| match heading {
+            h if h.starts_with("Global")     => "Global".to_string(),
+            h if h.starts_with("Major Mode") => "Major Mode".to_string(),
+            h                                => h.trim_end_matches(" Bindings").to_string(),
+        });
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    const DESCRIBE_BINDINGS: &str = "\
+Key translations:
+key             binding
+---             -------
+
+C-x 8 SPC
+
+\u{c}
+`paredit-mode' Minor Mode Bindings:
+key             binding
+---             -------
+
+C-M-f		paredit-forward
+M-S		paredit-split-sexp
+
+\u{c}
+Major Mode Bindings:
+key             binding
+---             -------
+
+C-c C-c		python-shell-send-buffer
+
+\u{c}
+Global Bindings:
+key             binding
+---             -------
+
+C-x		Control-X-prefix
+C-0 .. C-2	digit-argument
+SPC .. ~	self-insert-command
+<mouse-1>	mouse-set-point
+C-x 4		ctl-x-4-prefix
+C-x 4 f		find-file-other-window
+s-<return>	toggle-frame-fullscreen
+<C-left>	left-word
+C-x C-k C-a
+				kmacro-add-counter
+";
+
+    #[test]
+    fn test_keystroke() {
+        assert_eq!(keystroke("C-M-%"), Some(vec![
+            Modifier(LeftControl), Modifier(LeftAlt), Char('%'),
+        ]));
+        assert_eq!(keystroke("C--"), Some(vec![Modifier(LeftControl), Char('-')]));
+        assert_eq!(keystroke("s-<return>"), Some(vec![Modifier(LeftMeta), Enter]));
+        assert_eq!(keystroke("<C-left>"), Some(vec![Modifier(LeftControl), Left]));
+        assert_eq!(keystroke("M-F"), Some(vec![Modifier(LeftAlt), Modifier(LeftShift), Char('f')]));
+        assert_eq!(keystroke("<down-mouse-1>"), None);
+    }
+
+    #[test]
+    fn test_parse_key_description() {
+        assert_eq!(parse_key_description("C-x 4 f"), vec![
+            Shortcut::sequence(vec![
+                vec![Modifier(LeftControl), Char('x')],
+                vec![Char('4')],
+                vec![Char('f')],
+            ]),
+        ]);
+
+        assert_eq!(parse_key_description("C-0 .. C-2"), vec![
+            Shortcut::new(vec![Modifier(LeftControl), Char('0')]),
+            Shortcut::new(vec![Modifier(LeftControl), Char('1')]),
+            Shortcut::new(vec![Modifier(LeftControl), Char('2')]),
+        ]);
+
+        assert!(parse_key_description("SPC .. ~").is_empty());
+    }
+
+    #[test]
+    fn test_category_name() {
+        assert_eq!(category_name("`paredit-mode' Minor Mode Bindings"), Some("paredit-mode".to_string()));
+        assert_eq!(category_name("Minor Mode Bindings for ‘lsp-mode’"), Some("lsp-mode".to_string()));
+        assert_eq!(category_name("Major Mode Bindings"), Some("Major Mode".to_string()));
+        assert_eq!(category_name("Global Bindings Starting With C-x"), Some("Global".to_string()));
+        assert_eq!(category_name("Key translations"), None);
+    }
+
+    #[test]
+    fn test_parse_emacs_bindings() {
+        let keymap = parse_emacs_bindings(DESCRIBE_BINDINGS);
+
+        assert_categories(&keymap, &[
+            ("Global", 5),
+            ("Major Mode", 1),
+            ("paredit-mode", 2),
+        ]);
+
+        let names: Vec<_> = keymap.actions_by_category("Global").iter()
+            .map(Action::name)
+            .collect();
+        assert_eq!(names, vec![
+            "digit-argument",
+            "find-file-other-window",
+            "toggle-frame-fullscreen",
+            "left-word",
+            "kmacro-add-counter",
+        ]);
+    }
+}
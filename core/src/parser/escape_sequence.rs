@@ -0,0 +1,287 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::parser::key_names;
+use std::iter::Peekable;
+use std::str::Chars;
+
+const ESC: char = '\x1b';
+
+/// Decodes the characters a terminal sends for a key binding, e.g. `\e[1;5C`
+/// or `^X^E`, into keystrokes. Shell line editors bind these raw sequences
+/// rather than key names; an `ESC` prefix is read as the alt modifier, which
+/// is how terminals send it.
+pub(crate) fn keystrokes(seq: &[char]) -> Option<Vec<Vec<KeyCode>>> {
+    let mut keystrokes = Vec::new();
+    let mut rest = seq;
+
+    while !rest.is_empty() {
+        let (keystroke, len) = keystroke(rest)?;
+        keystrokes.push(keystroke);
+        rest = &rest[len..];
+    }
+
+    (!keystrokes.is_empty()).then_some(keystrokes)
+}
+
+/// Resolves the backslash escapes readline and zsh use to spell key sequences:
+/// `\C-x`, `\M-x` (an `ESC` prefix), `\e` and C-style escapes. zsh also
+/// reads caret notation, e.g. `^X^E`.
+pub(crate) fn unescape(s: &str, caret_notation: bool) -> Option<Vec<char>> {
+    let mut chars = s.chars().peekable();
+    let mut seq = Vec::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => seq.extend(escape(&mut chars, caret_notation)?),
+            '^' if caret_notation && chars.peek().is_some() => seq.push(control(chars.next()?)),
+            c    => seq.push(c),
+        }
+    }
+
+    Some(seq)
+}
+
+/// The character sent for ctrl + `c`.
+pub(crate) fn control(c: char) -> char {
+    match c {
+        '?' => '\x7f',
+        c   => ((c.to_ascii_uppercase() as u8) & 0x1f) as char,
+    }
+}
+
+/// Resolves the escape following a `\`.
+fn escape(chars: &mut Peekable<Chars>, caret_notation: bool) -> Option<Vec<char>> {
+    let c = chars.next()?;
+    let escaped = match c {
+        'C' | 'M' if chars.peek() == Some(&'-') => {
+            chars.next();
+            let mut key = match chars.next()? {
+                '\\'                  => escape(chars, caret_notation)?,
+                '^' if caret_notation => vec![control(chars.next()?)],
+                c                     => vec![c],
+            };
+
+            if c == 'C' {
+                let last = key.pop()?;
+                key.push(control(last));
+                key
+            } else {
+                // meta is sent as an ESC prefix
+                std::iter::once(ESC).chain(key).collect()
+            }
+        },
+        'e' | 'E' => vec![ESC],
+        'a'       => vec!['\x07'],
+        'b'       => vec!['\x08'],
+        'd'       => vec!['\x7f'],
+        'f'       => vec!['\x0c'],
+        'n'       => vec!['\n'],
+        'r'       => vec!['\r'],
+        't'       => vec!['\t'],
+        'v'       => vec!['\x0b'],
+        'x'       => {
+            let hex: String = std::iter::from_fn(|| chars.next_if(char::is_ascii_hexdigit))
+                .take(2)
+                .collect();
+            vec![char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?]
+        },
+        '0'..='7' => {
+            let octal: String = std::iter::once(c)
+                .chain(std::iter::from_fn(|| chars.next_if(|c| ('0'..='7').contains(c))).take(2))
+                .collect();
+            vec![char::from_u32(u32::from_str_radix(&octal, 8).ok()?)?]
+        },
+        c         => vec![c],
+    };
+
+    Some(escaped)
+}
+
+/// Decodes the first keystroke of `seq`, returning it along with the number
+/// of characters consumed.
+fn keystroke(seq: &[char]) -> Option<(Vec<KeyCode>, usize)> {
+    match seq {
+        [ESC, '[']            => Some((alt(vec![KeyCode::Char('[')]), 2)),
+        [ESC, '[', rest @ ..] => csi(rest).map(|(keystroke, len)| (keystroke, len + 2)),
+        [ESC, 'O', c, ..] if ss3(*c).is_some() => Some((ss3(*c)?, 3)),
+        [ESC]                 => Some((vec![KeyCode::Esc], 1)),
+        [ESC, ..]             => {
+            let (keystroke, len) = keystroke(&seq[1..])?;
+            Some((alt(keystroke), len + 1))
+        },
+        [c, ..]               => Some((character(*c)?, 1)),
+        []                    => None,
+    }
+}
+
+fn alt(mut keystroke: Vec<KeyCode>) -> Vec<KeyCode> {
+    keystroke.insert(0, KeyCode::Modifier(ModifierKeyCode::LeftAlt));
+    keystroke
+}
+
+fn ctrl(key_code: KeyCode) -> Vec<KeyCode> {
+    vec![KeyCode::Modifier(ModifierKeyCode::LeftControl), key_code]
+}
+
+/// A single character, including the C0 control characters produced by
+/// holding ctrl.
+fn character(c: char) -> Option<Vec<KeyCode>> {
+    use KeyCode::*;
+
+    let keystroke = match c {
+        '\0'                 => ctrl(Char(' ')),
+        '\t'                 => vec![Tab],
+        '\r'                 => vec![Enter],
+        '\x7f'               => vec![Backspace],
+        '\x01'..='\x1a'      => ctrl(Char((c as u8 + b'a' - 1) as char)),
+        '\x1c'..='\x1f'      => ctrl(Char((c as u8 + b'@') as char)),
+        c if c.is_control()  => return None,
+        c                    => key_names::unshifted(Char(c)),
+    };
+
+    Some(keystroke)
+}
+
+/// Control sequences (`ESC [`), as sent by xterm-compatible terminals for
+/// cursor, editing and function keys. Modifiers are encoded as the last
+/// parameter, e.g. `ESC [ 1 ; 5 C` is ctrl+right.
+fn csi(seq: &[char]) -> Option<(Vec<KeyCode>, usize)> {
+    use KeyCode::*;
+
+    let params_len = seq.iter()
+        .position(|c| !c.is_ascii_digit() && *c != ';')?;
+    let params: String = seq[..params_len].iter().collect();
+    let params: Vec<u32> = params.split(';')
+        .map(|p| p.parse().unwrap_or(1))
+        .collect();
+
+    let key_code = match (seq[params_len], params[0]) {
+        ('A', _)             => Up,
+        ('B', _)             => Down,
+        ('C', _)             => Right,
+        ('D', _)             => Left,
+        ('H', _)             => Home,
+        ('F', _)             => End,
+        ('Z', _)             => BackTab,
+        ('P', _)             => F(1),
+        ('Q', _)             => F(2),
+        ('R', _)             => F(3),
+        ('S', _)             => F(4),
+        // rxvt: shift+arrow
+        ('a', _)             => return Some((shift(Up), params_len + 1)),
+        ('b', _)             => return Some((shift(Down), params_len + 1)),
+        ('c', _)             => return Some((shift(Right), params_len + 1)),
+        ('d', _)             => return Some((shift(Left), params_len + 1)),
+        ('~', 1 | 7)         => Home,
+        ('~', 2)             => Insert,
+        ('~', 3)             => Delete,
+        ('~', 4 | 8)         => End,
+        ('~', 5)             => PageUp,
+        ('~', 6)             => PageDown,
+        ('~', n @ 11..=15)   => F(n as u8 - 10),
+        ('~', n @ 17..=21)   => F(n as u8 - 11),
+        ('~', n @ 23..=26)   => F(n as u8 - 12),
+        ('~', n @ 28..=29)   => F(n as u8 - 13),
+        ('~', n @ 31..=34)   => F(n as u8 - 14),
+        // "CSI u", the fixterms/kitty keyboard protocol
+        ('u', n)             => match char::from_u32(n)? {
+            '\r'   => Enter,
+            '\t'   => Tab,
+            '\x1b' => Esc,
+            '\x7f' => Backspace,
+            c      => Char(c),
+        },
+        _                    => return None,
+    };
+
+    let modifiers = params.get(1).copied().unwrap_or(1);
+    let mut keystroke = csi_modifiers(modifiers);
+    keystroke.extend(key_names::unshifted(key_code));
+
+    Some((keystroke, params_len + 1))
+}
+
+fn shift(key_code: KeyCode) -> Vec<KeyCode> {
+    vec![KeyCode::Modifier(ModifierKeyCode::LeftShift), key_code]
+}
+
+/// The xterm modifier parameter: 1 plus a bitmask of shift (1), alt (2),
+/// ctrl (4) and meta (8).
+fn csi_modifiers(param: u32) -> Vec<KeyCode> {
+    use ModifierKeyCode::*;
+
+    let mask = param.saturating_sub(1);
+    [(1, LeftShift), (2, LeftAlt), (4, LeftControl), (8, LeftMeta)].into_iter()
+        .filter(|(bit, _)| mask & bit != 0)
+        .map(|(_, m)| KeyCode::Modifier(m))
+        .collect()
+}
+
+/// Single shift sequences (`ESC O`), sent for cursor keys in application mode
+/// and for F1-F4.
+fn ss3(c: char) -> Option<Vec<KeyCode>> {
+    use KeyCode::*;
+
+    let keystroke = match c {
+        'A' => vec![Up],
+        'B' => vec![Down],
+        'C' => vec![Right],
+        'D' => vec![Left],
+        'H' => vec![Home],
+        'F' => vec![End],
+        'M' => vec![Enter],
+        'P' => vec![F(1)],
+        'Q' => vec![F(2)],
+        'R' => vec![F(3)],
+        'S' => vec![F(4)],
+        // rxvt: ctrl+arrow
+        'a' => ctrl(Up),
+        'b' => ctrl(Down),
+        'c' => ctrl(Right),
+        'd' => ctrl(Left),
+        _   => return None,
+    };
+
+    Some(keystroke)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    fn decode(seq: &str) -> Option<Vec<Vec<KeyCode>>> {
+        keystrokes(&seq.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_keystrokes() {
+        assert_eq!(decode("\x1b[1;5C"), Some(vec![vec![Modifier(LeftControl), Right]]));
+        assert_eq!(decode("\x1b[3~"), Some(vec![vec![Delete]]));
+        assert_eq!(decode("\x1b[15;2~"), Some(vec![vec![Modifier(LeftShift), F(5)]]));
+        assert_eq!(decode("\x1bOA"), Some(vec![vec![Up]]));
+        assert_eq!(decode("\x1b\x1b[D"), Some(vec![vec![Modifier(LeftAlt), Left]]));
+        assert_eq!(decode("\x18\x05"), Some(vec![
+            vec![Modifier(LeftControl), Char('x')],
+            vec![Modifier(LeftControl), Char('e')],
+        ]));
+        assert_eq!(decode("\x1bB"), Some(vec![vec![Modifier(LeftAlt), Modifier(LeftShift), Char('b')]]));
+        assert_eq!(decode("\x1f"), Some(vec![vec![Modifier(LeftControl), Char('_')]]));
+        assert_eq!(decode("\x1b"), Some(vec![vec![Esc]]));
+        assert_eq!(decode("\x7f"), Some(vec![vec![Backspace]]));
+        assert_eq!(decode("\x1b[200~"), None);
+        assert_eq!(decode(""), None);
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r"\C-x\C-e", false), Some(vec!['\x18', '\x05']));
+        assert_eq!(unescape(r"\M-\C-h", false), Some(vec![ESC, '\x08']));
+        assert_eq!(unescape(r"\e[1;5C", false), Some("\x1b[1;5C".chars().collect()));
+        assert_eq!(unescape(r"\033\x7f", false), Some(vec![ESC, '\x7f']));
+        assert_eq!(unescape(r"^X^?", true), Some(vec!['\x18', '\x7f']));
+        assert_eq!(unescape(r"\M-^@", true), Some(vec![ESC, '\0']));
+        assert_eq!(unescape(r"^", true), Some(vec!['^']));
+        assert_eq!(unescape(r"^X", false), Some(vec!['^', 'X']));
+    }
+}
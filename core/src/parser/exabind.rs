@@ -0,0 +1,174 @@
+use crate::export::ShortcutRecord;
+use crate::keymap::KeyMap;
+use crate::parser::{parse_notation, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_SOURCE: &str = "exabind";
+
+/// Parses a keymap in exabind's own JSON schema, as written by `exabind
+/// export --format json`; see [ShortcutRecord]. Records sharing source,
+/// category and id make up one action, with the shortcuts in record order;
+/// records with empty keystrokes only add a default shortcut. Keymaps with
+/// several sources are [merged](KeyMap::merge).
+///
+/// Fails on invalid JSON, and on records with keystrokes that don't parse,
+/// e.g. a misspelled key name.
+pub fn parse_exabind_json(input: &str) -> Result<KeyMap, String> {
+    let records: Vec<ShortcutRecord> = serde_json::from_str(input)
+        .map_err(|e| e.to_string())?;
+
+    // keyed by source, then by category and id
+    let mut sources: BTreeMap<String, BTreeMap<(String, String), ActionRecords>> = BTreeMap::new();
+    for (i, record) in records.into_iter().enumerate() {
+        let notation = |keystrokes: &str| parse_notation(keystrokes).ok_or(format!(
+            "record {} ({}/{}): invalid keystrokes \"{keystrokes}\"", i + 1, record.category, record.id
+        ));
+
+        let shortcut = match record.keystrokes.as_str() {
+            ""         => None,
+            keystrokes => Some(notation(keystrokes)?),
+        };
+        let default_shortcut = record.default.as_deref().map(notation).transpose()?;
+
+        let source = match record.source.as_str() {
+            ""     => DEFAULT_SOURCE.to_string(),
+            source => source.to_string(),
+        };
+
+        let action = sources.entry(source)
+            .or_default()
+            .entry((record.category, record.id))
+            .or_default();
+
+        if !record.label.is_empty() {
+            action.label = Some(record.label);
+        }
+        action.shortcuts.extend(shortcut);
+        action.default_shortcuts.extend(default_shortcut);
+    }
+
+    let keymaps = sources.into_iter()
+        .map(|(source, actions)| {
+            let (actions, unbound) = by_category(actions);
+            KeyMap::new(source, actions).with_unbound_actions(unbound)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(match keymaps.is_empty() {
+        true  => KeyMap::new(DEFAULT_SOURCE, HashMap::new()),
+        false => KeyMap::merge(keymaps),
+    })
+}
+
+pub struct ExabindFormat;
+
+impl KeymapFormat for ExabindFormat {
+    fn name(&self) -> &'static str {
+        "exabind"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["*.exabind.json"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.trim_start().starts_with('[') && input.contains("\"keystrokes\"")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_exabind_json(input)
+    }
+}
+
+#[derive(Default)]
+struct ActionRecords {
+    label: Option<String>,
+    shortcuts: Vec<Shortcut>,
+    default_shortcuts: Vec<Shortcut>,
+}
+
+/// The actions by category, and the actions without shortcuts.
+fn by_category(actions: BTreeMap<(String, String), ActionRecords>) -> (HashMap<String, Vec<Action>>, Vec<Action>) {
+    let mut by_category: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut unbound: Vec<Action> = Vec::new();
+    for ((category, id), records) in actions {
+        let mut action = Action::new_filter_empty(id.as_str(), category.as_str(), records.shortcuts)
+            .with_default_shortcuts(records.default_shortcuts);
+
+        if let Some(label) = records.label {
+            action = action.with_label(label);
+        }
+
+        if action.shortcuts().is_empty() {
+            unbound.push(action);
+            continue;
+        }
+
+        by_category.entry(category)
+            .or_default()
+            .push(action);
+    }
+
+    (by_category, unbound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{export, ExportFormat};
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+
+    #[test]
+    fn test_parse_exabind_json() {
+        let input = r#"[
+  { "category": "kwin", "id": "Overview", "label": "Toggle Overview", "keystrokes": "Meta+w", "default": "Meta+w" },
+  { "category": "kwin", "id": "Overview", "keystrokes": "Meta+Tab" },
+  { "category": "kwin", "id": "Expose", "keystrokes": "Ctrl+F9" },
+  { "category": "kwin", "id": "Show Desktop", "keystrokes": "", "default": "Meta+d" }
+]"#;
+
+        let keymap = parse_exabind_json(input).unwrap();
+        assert_eq!(keymap.name(), "exabind");
+        assert_categories(&keymap, &[("kwin", 2)]);
+
+        let actions = keymap.actions_by_category("kwin");
+        assert_eq!(actions[0].name(), "Expose");
+        assert_eq!(actions[1].id(), "Overview");
+        assert_eq!(actions[1].name(), "Toggle Overview");
+        assert_eq!(actions[1].shortcuts(), &[
+            Shortcut::new(vec![Modifier(LeftMeta), Char('w')]),
+            Shortcut::new(vec![Modifier(LeftMeta), Tab]),
+        ]);
+        assert_eq!(actions[1].default_shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('w')])]);
+
+        let unbound = keymap.unbound_actions();
+        assert_eq!(unbound.len(), 1);
+        assert_eq!(unbound[0].id(), "Show Desktop");
+        assert_eq!(unbound[0].default_shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('d')])]);
+    }
+
+    #[test]
+    fn test_invalid_exabind_json() {
+        let error = parse_exabind_json(r#"[{ "category": "kwin", "id": "Expose" "keystrokes": "Ctrl+F9" }]"#);
+        assert_eq!(error.unwrap_err(), "expected `,` or `}` at line 1 column 39");
+
+        let error = parse_exabind_json(r#"[
+  { "category": "kwin", "id": "Expose", "keystrokes": "Ctrl+F9" },
+  { "category": "kwin", "id": "Broken", "keystrokes": "Ctrl+Nope" }
+]"#);
+        assert_eq!(error.unwrap_err(), r#"record 2 (kwin/Broken): invalid keystrokes "Ctrl+Nope""#);
+
+        let error = parse_exabind_json(r#"[{ "category": "kwin", "id": "Expose", "keystrokes": "Ctrl+F9", "default": "F99" }]"#);
+        assert_eq!(error.unwrap_err(), r#"record 1 (kwin/Expose): invalid keystrokes "F99""#);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let input = include_str!("../../../test/kglobalshortcuts.exabind.json");
+        let exported = export(&parse_exabind_json(input).unwrap(), ExportFormat::Json).unwrap();
+        assert_eq!(exported, input);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::shell::{self, Word};
+use crate::parser::{escape_sequence, format::any_line_starts_with, key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+const DEFAULT_CATEGORY: &str = "default";
+
+/// Parses the output of fish's `bind` into a [KeyMap], with one category per
+/// bind mode (`-M insert`).
+///
+/// Both key notations are understood: the escape sequences of fish 3 (`\cx\ce`,
+/// `\e\[1\;5C`, `-k right`) and the key names of fish 4 (`ctrl-x,ctrl-e`).
+pub fn parse_fish_bind(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+
+    input.lines()
+        .filter_map(bind)
+        .for_each(|(category, action)| actions.entry(category).or_default().push(action));
+
+    KeyMap::new("fish", actions)
+}
+
+pub struct FishFormat;
+
+impl KeymapFormat for FishFormat {
+    fn name(&self) -> &'static str {
+        "fish"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["fish_user_key_bindings.fish"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        any_line_starts_with(input, "bind ")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_fish_bind(input))
+    }
+}
+
+/// `bind --preset -M insert -m default \cc cancel-commandline repaint-mode`
+fn bind(line: &str) -> Option<(String, Action)> {
+    let mut words = words(line)?.into_iter();
+    if words.next()?.text != "bind" {
+        return None;
+    }
+
+    let mut category = DEFAULT_CATEGORY.to_string();
+    let mut new_mode = None;
+    let mut terminfo = false;
+
+    let key = loop {
+        let word = words.next()?;
+        let option = word.text.split_once('=');
+        match (word.text.as_str(), option) {
+            ("-M" | "--mode", _)             => category = words.next()?.text,
+            (_, Some(("--mode", mode)))      => category = mode.to_string(),
+            ("-m" | "--sets-mode", _)        => new_mode = Some(words.next()?.text),
+            (_, Some(("--sets-mode", mode))) => new_mode = Some(mode.to_string()),
+            ("-k" | "--key", _)              => terminfo = true,
+            ("-e" | "--erase", _)            => return None,
+            ("--", _)                        => break words.next()?,
+            (w, _) if w.starts_with('-') && w.len() > 1 && word.raw => (),
+            _                                => break word,
+        }
+    };
+
+    let mut commands: Vec<String> = words
+        .map(|w| w.text)
+        .filter(|c| !c.is_empty() && !c.starts_with("self-insert"))
+        .collect();
+    if let Some(mode) = new_mode {
+        commands.push(format!("enter {mode} mode"));
+    }
+    if commands.is_empty() {
+        return None;
+    }
+
+    let keystrokes = if terminfo {
+        vec![terminfo_key(&key.text)?]
+    } else if key.raw {
+        key_names_sequence(&key.text)
+            .or_else(|| escape_sequence::keystrokes(&key.text.chars().collect::<Vec<_>>()))?
+    } else {
+        escape_sequence::keystrokes(&key.text.chars().collect::<Vec<_>>())?
+    };
+
+    let shortcut = Shortcut::sequence(keystrokes);
+    Some((category.clone(), Action::new_filter_empty(commands.join(", "), category, vec![shortcut])))
+}
+
+/// Splits a line into words, resolving fish's backslash escapes; returns
+/// `None` on an unterminated quote.
+fn words(line: &str) -> Option<Vec<Word>> {
+    shell::words(line, true, |quote, chars| match quote {
+        // only the quote itself and backslashes are escaped within quotes
+        Some(q) => Some(chars.next_if(|&c| c == q || c == '\\').unwrap_or('\\').to_string()),
+        None    => escape(chars).map(String::from),
+    })
+}
+
+/// Resolves an unquoted backslash escape, e.g. `\cx`, `\e` or `\x7f`.
+fn escape(chars: &mut Peekable<Chars>) -> Option<char> {
+    let escaped = match chars.next()? {
+        'c'       => escape_sequence::control(chars.next()?),
+        'e'       => '\x1b',
+        'a'       => '\x07',
+        'b'       => '\x08',
+        'f'       => '\x0c',
+        'n'       => '\n',
+        'r'       => '\r',
+        't'       => '\t',
+        'v'       => '\x0b',
+        'x' | 'X' => number(chars, 16, 2)?,
+        'u'       => number(chars, 16, 4)?,
+        'U'       => number(chars, 16, 8)?,
+        'o'       => number(chars, 8, 3)?,
+        c         => c,
+    };
+
+    Some(escaped)
+}
+
+/// A character code of up to `len` digits.
+fn number(chars: &mut Peekable<Chars>, radix: u32, len: usize) -> Option<char> {
+    let digits: String = std::iter::from_fn(|| chars.next_if(|c| c.is_digit(radix)))
+        .take(len)
+        .collect();
+
+    char::from_u32(u32::from_str_radix(&digits, radix).ok()?)
+}
+
+/// The key names of fish 4, e.g. `ctrl-x,ctrl-e` or `alt-shift-left`.
+fn key_names_sequence(keys: &str) -> Option<Vec<Vec<KeyCode>>> {
+    // a single character is bound as-is
+    if keys.chars().count() < 2 {
+        return None;
+    }
+
+    keys.split(',')
+        .map(|keystroke| {
+            let mut names: Vec<&str> = keystroke.split('-').collect();
+            let key = match names.pop()? {
+                // `alt--`
+                "" if names.last() == Some(&"") => { names.pop(); "-" },
+                key => key,
+            };
+
+            let mut keystroke = names.into_iter()
+                .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+                .collect::<Option<Vec<_>>>()?;
+
+            keystroke.extend(key_names::unshifted(key_names::key_code(key)?));
+            Some(keystroke)
+        })
+        .collect()
+}
+
+/// Key names from terminfo, as used by `bind -k`.
+fn terminfo_key(name: &str) -> Option<Vec<KeyCode>> {
+    use KeyCode::*;
+
+    let shift = |key_code| vec![Modifier(ModifierKeyCode::LeftShift), key_code];
+    let keystroke = match name {
+        "nul"       => vec![Modifier(ModifierKeyCode::LeftControl), Char(' ')],
+        "btab"      => vec![BackTab],
+        "ppage"     => vec![PageUp],
+        "npage"     => vec![PageDown],
+        "dc"        => vec![Delete],
+        "ic"        => vec![Insert],
+        "sdc"       => shift(Delete),
+        "sleft"     => shift(Left),
+        "sright"    => shift(Right),
+        "shome"     => shift(Home),
+        "send"      => shift(End),
+        "sic"       => shift(Insert),
+        name        => vec![key_names::key_code(name)?],
+    };
+
+    Some(keystroke)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_fish_bind() {
+        let input = r#"
+bind --preset '' self-insert
+bind --preset \cd delete-or-exit
+bind --preset \e\[1\;5C forward-word
+bind --preset -k right forward-char
+bind --preset \cx\ce edit_command_buffer
+bind --preset ctrl-x,ctrl-e edit_command_buffer
+bind --preset alt-left prevd-or-backward-word
+bind --preset \ed 'kill-word'
+bind ab 'commandline -i "hello"'
+bind --preset -M insert -m default \e cancel repaint-mode
+bind --preset -M visual -m default \cc end-selection
+"#;
+
+        let keymap = parse_fish_bind(input);
+
+        assert_categories(&keymap, &[
+            ("default", 8),
+            ("insert", 1),
+            ("visual", 1),
+        ]);
+
+        let actions = keymap.actions_by_category("default");
+        let ctrl = |c| vec![Modifier(LeftControl), Char(c)];
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(ctrl('d'))]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Right])]);
+        assert_eq!(actions[2].shortcuts(), &[Shortcut::new(vec![Right])]);
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::sequence(vec![ctrl('x'), ctrl('e')])]);
+        assert_eq!(actions[4].shortcuts(), actions[3].shortcuts());
+        assert_eq!(actions[5].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Left])]);
+        assert_eq!(actions[6].name(), "kill-word");
+        assert_eq!(actions[6].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Char('d')])]);
+        assert_eq!(actions[7].name(), "commandline -i \"hello\"");
+        assert_eq!(actions[7].shortcuts(), &[Shortcut::sequence(vec![vec![Char('a')], vec![Char('b')]])]);
+
+        let insert = keymap.actions_by_category("insert");
+        assert_eq!(insert[0].name(), "cancel, repaint-mode, enter default mode");
+        assert_eq!(insert[0].shortcuts(), &[Shortcut::new(vec![Esc])]);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::keymap::KeyMap;
+use std::path::Path;
+
+/// A keymap file format: how to recognize a file of the format, and how to
+/// parse it into a [KeyMap].
+pub trait KeymapFormat: Sync {
+    /// Name of the format, as given to `--keymap-format`.
+    fn name(&self) -> &'static str;
+
+    /// Globs matching the paths the format is usually found at, e.g.
+    /// `kitty.conf` or `helix/config.toml`. A glob is matched against the
+    /// trailing components of a path, and `*` matches within a component.
+    fn file_globs(&self) -> &'static [&'static str];
+
+    /// Whether `input` looks like this format.
+    fn sniff(&self, input: &str) -> bool;
+
+    /// Parses `input` into a [KeyMap], or describes why it isn't valid.
+    fn parse(&self, input: &str) -> Result<KeyMap, String>;
+
+    /// Whether `path` matches any of the format's [file globs](Self::file_globs).
+    fn matches_path(&self, path: &Path) -> bool {
+        self.file_globs().iter().any(|glob| glob_matches(glob, path))
+    }
+}
+
+fn glob_matches(glob: &str, path: &Path) -> bool {
+    let components: Vec<String> = path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    let patterns: Vec<&str> = glob.split('/').collect();
+    if patterns.len() > components.len() {
+        return false;
+    }
+
+    components[components.len() - patterns.len()..].iter()
+        .zip(patterns)
+        .all(|(component, pattern)| wildcard_matches(pattern, component))
+}
+
+fn wildcard_matches(pattern: &str, s: &str) -> bool {
+    match pattern.split_once('*') {
+        None                 => pattern == s,
+        Some((head, tail)) => s.strip_prefix(head).is_some_and(|rest| {
+            (0..=rest.len())
+                .filter(|i| rest.is_char_boundary(*i))
+                .any(|i| wildcard_matches(tail, &rest[i..]))
+        }),
+    }
+}
+
+/// Whether any line, with leading whitespace trimmed, starts with `prefix`.
+pub(crate) fn any_line_starts_with(input: &str, prefix: &str) -> bool {
+    input.lines().any(|line| line.trim_start().starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        let path = Path::new("/home/user/.config/helix/config.toml");
+        assert!(glob_matches("config.toml", path));
+        assert!(glob_matches("helix/config.toml", path));
+        assert!(glob_matches("*.toml", path));
+        assert!(glob_matches("h*x/*", path));
+        assert!(!glob_matches("zellij/config.toml", path));
+        assert!(!glob_matches("*.kdl", path));
+        assert!(!glob_matches("a/b/c/d/e/f/g/config.toml", path));
+    }
+}
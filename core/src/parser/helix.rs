@@ -0,0 +1,173 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{format::any_line_starts_with, key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+use toml::{Table, Value};
+
+/// Parses the `[keys.<mode>]` tables of a Helix `config.toml` into a [KeyMap],
+/// with one category per mode. Nested tables, e.g. `[keys.normal.space]` or
+/// `g = { a = "code_action" }`, become keystroke sequences.
+pub fn parse_helix_config(input: &str) -> Result<KeyMap, String> {
+    let config: Table = input.parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+
+    let modes = config.get("keys")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flatten();
+
+    let actions: HashMap<String, Vec<Action>> = modes
+        .filter_map(|(mode, keys)| Some((mode, keys.as_table()?)))
+        .map(|(mode, keys)| {
+            let mut actions = Vec::new();
+            collect_actions(mode, &[], keys, &mut actions);
+            (mode.clone(), actions)
+        })
+        .filter(|(_, actions)| !actions.is_empty())
+        .collect();
+
+    Ok(KeyMap::new("Helix", actions))
+}
+
+pub struct HelixFormat;
+
+impl KeymapFormat for HelixFormat {
+    fn name(&self) -> &'static str {
+        "helix"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["helix/config.toml"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        any_line_starts_with(input, "[keys.")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_helix_config(input)
+    }
+}
+
+fn collect_actions(
+    mode: &str,
+    prefix: &[Vec<KeyCode>],
+    keys: &Table,
+    actions: &mut Vec<Action>,
+) {
+    for (key, value) in keys {
+        let Some(keystroke) = keystroke(key) else { continue };
+
+        let mut keystrokes = prefix.to_vec();
+        keystrokes.push(keystroke);
+
+        match value {
+            Value::Table(minor_mode) => collect_actions(mode, &keystrokes, minor_mode, actions),
+            command => {
+                let Some(label) = command_label(command) else { continue };
+                actions.push(Action::new_filter_empty(label, mode.to_string(), vec![Shortcut::sequence(keystrokes)]));
+            },
+        }
+    }
+}
+
+/// A command (`"file_picker"`, `":write"`) or a list of commands, run in order.
+fn command_label(command: &Value) -> Option<String> {
+    match command {
+        Value::String(command) if command == "no_op" => None,
+        Value::String(command) => Some(command.clone()),
+        Value::Array(commands) => Some(commands.iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")),
+        _ => None,
+    }
+}
+
+/// Parses a helix key, e.g. `C-s`, `A-S-left` or `G`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    let mut parts: Vec<&str> = key.split('-').collect();
+    let key = match parts.pop()? {
+        // `C--` is ctrl and the minus key
+        "" if parts.last() == Some(&"") => { parts.pop(); "-" },
+        key => key,
+    };
+
+    let mut keystroke = parts.into_iter()
+        .map(|m| modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.extend(key_names::unshifted(key_code(key)?));
+    Some(keystroke)
+}
+
+fn modifier(name: &str) -> Option<ModifierKeyCode> {
+    match name {
+        "C" => Some(ModifierKeyCode::LeftControl),
+        "A" => Some(ModifierKeyCode::LeftAlt),
+        "S" => Some(ModifierKeyCode::LeftShift),
+        m   => key_names::modifier(m),
+    }
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    match name {
+        "lt"   => Some(KeyCode::Char('<')),
+        "gt"   => Some(KeyCode::Char('>')),
+        "null" => None,
+        n      => key_names::key_code(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_keystroke() {
+        assert_eq!(keystroke("C-s"), Some(vec![Modifier(LeftControl), Char('s')]));
+        assert_eq!(keystroke("A-S-left"), Some(vec![Modifier(LeftAlt), Modifier(LeftShift), Left]));
+        assert_eq!(keystroke("G"), Some(vec![Modifier(LeftShift), Char('g')]));
+        assert_eq!(keystroke("C--"), Some(vec![Modifier(LeftControl), Char('-')]));
+        assert_eq!(keystroke("X-a"), None);
+    }
+
+    #[test]
+    fn test_parse_helix_config() {
+        let input = r#"
+theme = "onedark"
+
+[keys.normal]
+C-s = ":w"
+g = { a = "code_action" }
+esc = ["collapse_selection", "keep_primary_selection"]
+
+[keys.normal.space]
+f = "file_picker"
+w = { v = "vsplit" }
+
+[keys.insert]
+C-x = "no_op"
+"#;
+
+        let keymap = parse_helix_config(input).unwrap();
+        assert_categories(&keymap, &[("normal", 5)]);
+
+        let shortcuts: HashMap<&str, &Shortcut> = keymap.actions_by_category("normal").iter()
+            .map(|a| (a.name(), &a.shortcuts()[0]))
+            .collect();
+
+        assert_eq!(shortcuts[":w"], &Shortcut::new(vec![Modifier(LeftControl), Char('s')]));
+        assert_eq!(shortcuts["code_action"], &Shortcut::sequence(vec![vec![Char('g')], vec![Char('a')]]));
+        assert_eq!(shortcuts["collapse_selection, keep_primary_selection"], &Shortcut::new(vec![Esc]));
+        assert_eq!(shortcuts["vsplit"], &Shortcut::sequence(vec![
+            vec![Char(' ')],
+            vec![Char('w')],
+            vec![Char('v')],
+        ]));
+    }
+}
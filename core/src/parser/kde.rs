@@ -1,5 +1,6 @@
 use crate::keymap::KeyMap;
 use crate::parser::kde::line::kglobalshortcuts_parser;
+use crate::parser::format::{any_line_starts_with, KeymapFormat};
 use crate::shortcut::{Action, Shortcut};
 use anpa::combinators::{attempt, many, many_to_vec, middle, no_separator, right, separator};
 use anpa::core::{parse, ParserExt, StrParser};
@@ -26,15 +27,57 @@ pub fn parse_kglobalshortcuts(input: &str) -> KeyMap {
 
     let rlines: Vec<ParsedLine> = parsed_lines.into_iter().rev().collect();
 
-    let actions: HashMap<String, Vec<Action>> = rlines
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut unbound: Vec<Action> = Vec::new();
+    for (category, category_actions) in rlines
         .split_inclusive(|line| matches!(line, ParsedLine::SectionHeader(_)))
         .filter_map(application_actions)
-        .collect();
+    {
+        let (bound, cleared): (Vec<Action>, Vec<Action>) = category_actions.into_iter()
+            .partition(|a| !a.shortcuts().is_empty());
+
+        unbound.extend(cleared);
+        if !bound.is_empty() {
+            actions.insert(category, bound);
+        }
+    }
 
     KeyMap::new("KDE", actions)
+        .with_unbound_actions(unbound)
+        .global()
+}
+
+pub struct KdeFormat;
+
+impl KeymapFormat for KdeFormat {
+    fn name(&self) -> &'static str {
+        "kde"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["kglobalshortcutsrc"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        let has_sections = any_line_starts_with(input, "[");
+        let has_shortcuts = input.lines()
+            .filter_map(|line| line.split_once('='))
+            .any(|(_, value)| value.matches(',').count() >= 2);
+
+        input.contains("_k_friendly_name=") || (has_sections && has_shortcuts)
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_kglobalshortcuts(input))
+    }
 }
 
 fn parse_shortcuts(s: &str) -> Vec<Shortcut> {
+    // defaults are frequently left blank
+    if s.is_empty() {
+        return Vec::new();
+    }
+
     let hmm = s.to_string();
     // let res = parse(keys::shortcut_keystrokes(), s);
     let res = parse(keys::shortcut_keystrokes(), hmm.as_str());
@@ -49,13 +92,16 @@ fn application_actions(rlines: &[ParsedLine]) -> Option<(String, Vec<Action>)> {
     rlines.iter().rev().for_each(|l| match l {
         ParsedLine::SectionHeader(s)                 => category = s,
         ParsedLine::SectionFriendlyName(s)           => category = s,
-        ParsedLine::Shortcut { shortcut, label, .. } => {
-            let shortcuts = parse_shortcuts(shortcut);
-            actions.push(Action::new_filter_empty(label, &"", shortcuts));
+        ParsedLine::Shortcut { id, shortcut, default_shortcut, label } => {
+            let action = Action::new_filter_empty(*id, "", parse_shortcuts(shortcut))
+                .with_label(label)
+                .with_default_shortcuts(parse_shortcuts(default_shortcut));
+            actions.push(action);
         }
     });
 
-    actions.retain(|a| !a.shortcuts().is_empty());
+    // unbound actions are kept for their default shortcuts
+    actions.retain(|a| !a.shortcuts().is_empty() || !a.default_shortcuts().is_empty());
     actions.iter_mut()
         .for_each(|a| a.update_category(category));
 
@@ -247,7 +293,7 @@ mod keys {
 
     #[cfg(test)]
     mod tests {
-        use crossterm::event::ModifierKeyCode;
+        use crate::key_event::ModifierKeyCode;
         use super::*;
 
         #[test]
@@ -332,7 +378,7 @@ mod tests {
         let input = "Ctrl+Alt+Esc\\tMeta+Alt+Down";
         let shortcuts = parse_shortcuts(input);
 
-        use crossterm::event::{KeyCode::*, ModifierKeyCode::*};
+        use crate::key_event::{KeyCode::*, ModifierKeyCode::*};
         assert_eq!(shortcuts, vec![
             Shortcut::new(vec![
                 Modifier(LeftControl),
@@ -360,19 +406,25 @@ mod tests {
     #[test]
     fn test_parse_keymap() -> std::io::Result<()> {
         let mut input = String::new();
-        let mut f = std::fs::File::open("./test/kglobalshortcutsrc")?;
+        let mut f = std::fs::File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/../test/kglobalshortcutsrc"))?;
         f.read_to_string(&mut input)?;
 
         let keymap = parse_kglobalshortcuts(&input);
         println!("{}", keymap);
 
+        // e.g. kwin's Expose=none,Ctrl+F9,Toggle Present Windows (Current desktop)
+        let unbound = keymap.unbound_actions();
+        assert!(!unbound.is_empty());
+        assert!(unbound.iter().all(|a| a.shortcuts().is_empty() && !a.default_shortcuts().is_empty()));
+        assert!(keymap.actions().all(|a| !a.shortcuts().is_empty()));
+
         Ok(())
     }
 
     #[test]
     fn test_kglobalshortcuts_parser()  -> std::io::Result<()> {
         let mut input = String::new();
-        let mut f = std::fs::File::open("./test/kglobalshortcutsrc")?;
+        let mut f = std::fs::File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/../test/kglobalshortcutsrc"))?;
         f.read_to_string(&mut input)?;
 
         let res = parse(kglobalshortcuts_parser(), &input);
@@ -0,0 +1,343 @@
+//! A lenient KDL reader, covering the parts of KDL v1 and v2 used by keymap
+//! configs. All values are kept as text and type annotations are discarded.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct KdlNode {
+    pub name: String,
+    pub args: Vec<String>,
+    pub props: Vec<(String, String)>,
+    pub children: Vec<KdlNode>,
+}
+
+impl KdlNode {
+    /// The value of a property; the last one wins if it is given twice.
+    pub fn prop(&self, name: &str) -> Option<&str> {
+        self.props.iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The node's name followed by its arguments, e.g. `spawn "foot" "-e"`
+    /// becomes `spawn foot -e`.
+    pub fn describe(&self) -> String {
+        std::iter::once(self.name.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Parses a KDL document; returns `None` if the document is malformed.
+pub(crate) fn parse_kdl(input: &str) -> Option<Vec<KdlNode>> {
+    let mut parser = Parser { src: input, pos: 0 };
+    let nodes = parser.nodes()?;
+
+    parser.skip_line_space();
+    (parser.pos == input.len()).then_some(nodes)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+enum Entry {
+    Arg(String),
+    Prop(String, String),
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn advance(&mut self) {
+        self.pos += self.peek().map(char::len_utf8).unwrap_or(0);
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let matches = self.rest().starts_with(s);
+        if matches {
+            self.pos += s.len();
+        }
+        matches
+    }
+
+    fn nodes(&mut self) -> Option<Vec<KdlNode>> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_line_space();
+            match self.peek() {
+                None | Some('}') => return Some(nodes),
+                _ if self.eat("/-") => {
+                    self.skip_node_space();
+                    self.node()?;
+                },
+                _ => nodes.push(self.node()?),
+            }
+        }
+    }
+
+    fn node(&mut self) -> Option<KdlNode> {
+        self.type_annotation()?;
+        let mut node = KdlNode {
+            name: self.value()?,
+            ..KdlNode::default()
+        };
+
+        loop {
+            let had_space = self.skip_node_space();
+            match self.peek() {
+                None | Some('}')  => return Some(node),
+                Some('\n' | ';')  => {
+                    self.advance();
+                    return Some(node);
+                },
+                Some('{')         => {
+                    self.advance();
+                    node.children = self.nodes()?;
+                    self.eat("}").then_some(())?;
+                },
+                _ if self.eat("/-") => {
+                    self.skip_node_space();
+                    if self.eat("{") {
+                        self.nodes()?;
+                        self.eat("}").then_some(())?;
+                    } else {
+                        self.entry()?;
+                    }
+                },
+                _ if !had_space  => return None,
+                _                => match self.entry()? {
+                    Entry::Arg(value)       => node.args.push(value),
+                    Entry::Prop(key, value) => node.props.push((key, value)),
+                },
+            }
+        }
+    }
+
+    fn entry(&mut self) -> Option<Entry> {
+        self.type_annotation()?;
+        let value = self.value()?;
+
+        if self.eat("=") {
+            self.type_annotation()?;
+            Some(Entry::Prop(value, self.value()?))
+        } else {
+            Some(Entry::Arg(value))
+        }
+    }
+
+    fn type_annotation(&mut self) -> Option<()> {
+        if self.eat("(") {
+            self.value()?;
+            self.eat(")").then_some(())?;
+        }
+        Some(())
+    }
+
+    fn value(&mut self) -> Option<String> {
+        let rest = self.rest();
+        if rest.starts_with('"') {
+            self.string()
+        } else if rest.starts_with("r#") || rest.starts_with("r\"") {
+            self.advance();
+            self.raw_string()
+        } else if rest.starts_with("#\"") || rest.starts_with("##") {
+            self.raw_string()
+        } else {
+            self.identifier()
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let is_delimiter = |c: char| c.is_whitespace() || "\\/(){}<>;[]=,\"".contains(c);
+        let len = self.rest().find(is_delimiter).unwrap_or(self.rest().len());
+        if len == 0 {
+            return None;
+        }
+
+        let identifier = &self.rest()[..len];
+        self.pos += len;
+
+        // v2 keywords
+        let identifier = match identifier {
+            "#true"  => "true",
+            "#false" => "false",
+            "#null"  => "null",
+            i        => i,
+        };
+
+        Some(identifier.to_string())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.eat("\"").then_some(())?;
+
+        let mut s = String::new();
+        loop {
+            let c = self.peek()?;
+            self.advance();
+            match c {
+                '"'  => return Some(s),
+                '\\' => {
+                    let escaped = self.peek()?;
+                    self.advance();
+                    match escaped {
+                        'n'  => s.push('\n'),
+                        'r'  => s.push('\r'),
+                        't'  => s.push('\t'),
+                        'b'  => s.push('\u{8}'),
+                        'f'  => s.push('\u{c}'),
+                        's'  => s.push(' '),
+                        'u'  => {
+                            self.eat("{").then_some(())?;
+                            let len = self.rest().find('}')?;
+                            let code = u32::from_str_radix(&self.rest()[..len], 16).ok()?;
+                            s.push(char::from_u32(code)?);
+                            self.pos += len + 1;
+                        },
+                        // v2 whitespace escape
+                        c if c.is_whitespace() => {
+                            let trimmed = self.rest().trim_start();
+                            self.pos = self.src.len() - trimmed.len();
+                        },
+                        c    => s.push(c),
+                    }
+                },
+                c    => s.push(c),
+            }
+        }
+    }
+
+    /// `#"..."#` (v2) or `r#"..."#` (v1, with the `r` already consumed)
+    fn raw_string(&mut self) -> Option<String> {
+        let hashes = self.rest().find(|c| c != '#')?;
+        self.pos += hashes;
+        self.eat("\"").then_some(())?;
+
+        let close = format!("\"{}", "#".repeat(hashes));
+        let len = self.rest().find(&close)?;
+        let s = self.rest()[..len].to_string();
+        self.pos += len + close.len();
+        Some(s)
+    }
+
+    /// Skips whitespace, newlines, comments and `;` between nodes.
+    fn skip_line_space(&mut self) {
+        loop {
+            let trimmed = self.rest().trim_start_matches(|c: char| c.is_whitespace() || c == ';');
+            self.pos = self.src.len() - trimmed.len();
+
+            if !self.skip_comment() {
+                return;
+            }
+        }
+    }
+
+    /// Skips whitespace, comments and escaped newlines within a node; returns
+    /// whether anything was skipped.
+    fn skip_node_space(&mut self) -> bool {
+        let start = self.pos;
+        loop {
+            let trimmed = self.rest().trim_start_matches(|c: char| c.is_whitespace() && c != '\n');
+            self.pos = self.src.len() - trimmed.len();
+
+            if self.rest().starts_with("//") {
+                // the newline terminates the node
+                let len = self.rest().find('\n').unwrap_or(self.rest().len());
+                self.pos += len;
+            } else if self.rest().starts_with("/*") {
+                self.skip_comment();
+            } else if self.rest().starts_with('\\') {
+                // line continuation
+                self.advance();
+                self.skip_node_space();
+                if !self.eat("\n") {
+                    self.eat("\r\n");
+                }
+            } else {
+                return self.pos != start;
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) -> bool {
+        if self.eat("//") {
+            let len = self.rest().find('\n').unwrap_or(self.rest().len());
+            self.pos += len;
+            true
+        } else if self.eat("/*") {
+            // block comments nest
+            let mut depth = 1;
+            while depth > 0 && self.peek().is_some() {
+                if self.eat("/*") {
+                    depth += 1;
+                } else if self.eat("*/") {
+                    depth -= 1;
+                } else {
+                    self.advance();
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kdl() {
+        let input = r##"
+// comment
+keybinds clear-defaults=true {
+    normal {
+        bind "Ctrl g" { SwitchToMode "locked"; }
+        /- bind "Alt x" { Quit; }
+        bind "Alt h" "Alt Left" { MoveFocus "Left"; }
+    }
+}
+binds {
+    Mod+T repeat=false { spawn r#"foot"# "-e" #"htop"#; }
+    /* multi
+       line */ Mod+Q { close-window; }
+    Mod+1 \
+        { focus-workspace 1; }
+}
+"##;
+
+        let nodes = parse_kdl(input).unwrap();
+        assert_eq!(nodes.len(), 2);
+
+        let keybinds = &nodes[0];
+        assert_eq!(keybinds.props, vec![("clear-defaults".to_string(), "true".to_string())]);
+
+        let normal = &keybinds.children[0];
+        assert_eq!(normal.children.len(), 2);
+        assert_eq!(normal.children[1].args, vec!["Alt h", "Alt Left"]);
+        assert_eq!(normal.children[1].children[0].describe(), "MoveFocus Left");
+
+        let binds = &nodes[1].children;
+        assert_eq!(binds.len(), 3);
+        assert_eq!(binds[0].name, "Mod+T");
+        assert_eq!(binds[0].props, vec![("repeat".to_string(), "false".to_string())]);
+        assert_eq!(binds[0].children[0].describe(), "spawn foot -e htop");
+        assert_eq!(binds[1].name, "Mod+Q");
+        assert_eq!(binds[2].children[0].describe(), "focus-workspace 1");
+    }
+
+    #[test]
+    fn test_malformed_kdl() {
+        assert_eq!(parse_kdl("node { child"), None);
+        assert_eq!(parse_kdl("node \"unterminated"), None);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::key_event::{KeyCode, MediaKeyCode, ModifierKeyCode};
+
+/// Resolves a key name, as spelled by X11 keysyms, Emacs, terminal emulators
+/// and most other config formats, to a [KeyCode]. Names are matched
+/// case-insensitively; single characters are returned as-is.
+pub(crate) fn key_code(name: &str) -> Option<KeyCode> {
+    use KeyCode::*;
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Char(c));
+    }
+
+    let name = name.to_ascii_lowercase();
+    let key_code = match name.as_str() {
+        "space" | "spc"                          => Char(' '),
+        "tab"                                    => Tab,
+        "backtab" | "iso_left_tab"               => BackTab,
+        "return" | "enter" | "ret" | "kp_enter"  => Enter,
+        "escape" | "esc"                         => Esc,
+        "backspace" | "bspace" | "bs"            => Backspace,
+        "delete" | "del" | "deletechar"          => Delete,
+        "insert" | "ins"                         => Insert,
+        "home"                                   => Home,
+        "end"                                    => End,
+        "pageup" | "page_up" | "pgup" | "prior"  => PageUp,
+        "pagedown" | "page_down" | "pgdn"
+        | "pgdown" | "next"                      => PageDown,
+        "left"                                   => Left,
+        "right"                                  => Right,
+        "up"                                     => Up,
+        "down"                                   => Down,
+        "print" | "printscreen" | "print_screen"
+        | "sysrq"                                => PrintScreen,
+        "pause" | "break"                        => Pause,
+        "menu" | "apps"                          => Menu,
+        "kp_begin" | "begin"                     => KeypadBegin,
+        "capslock" | "caps_lock"                 => CapsLock,
+        "scrolllock" | "scroll_lock"             => ScrollLock,
+        "numlock" | "num_lock"                   => NumLock,
+        // punctuation, as spelled by X11 keysyms
+        "minus"                                  => Char('-'),
+        "equal"                                  => Char('='),
+        "plus"                                   => Char('+'),
+        "bracketleft"                            => Char('['),
+        "bracketright"                           => Char(']'),
+        "braceleft"                              => Char('{'),
+        "braceright"                             => Char('}'),
+        "backslash"                              => Char('\\'),
+        "bar"                                    => Char('|'),
+        "semicolon"                              => Char(';'),
+        "colon"                                  => Char(':'),
+        "apostrophe" | "quoteright"              => Char('\''),
+        "quotedbl"                               => Char('"'),
+        "grave" | "quoteleft"                    => Char('`'),
+        "asciitilde" | "tilde"                   => Char('~'),
+        "comma"                                  => Char(','),
+        "period"                                 => Char('.'),
+        "slash"                                  => Char('/'),
+        "question"                               => Char('?'),
+        "less"                                   => Char('<'),
+        "greater"                                => Char('>'),
+        "exclam"                                 => Char('!'),
+        "at"                                     => Char('@'),
+        "numbersign"                             => Char('#'),
+        "dollar"                                 => Char('$'),
+        "percent"                                => Char('%'),
+        "asciicircum"                            => Char('^'),
+        "ampersand"                              => Char('&'),
+        "asterisk"                               => Char('*'),
+        "parenleft"                              => Char('('),
+        "parenright"                             => Char(')'),
+        "underscore"                             => Char('_'),
+        // media keys
+        "xf86audioplay" | "mediaplay"            => Media(MediaKeyCode::Play),
+        "xf86audiopause" | "mediapause"          => Media(MediaKeyCode::Pause),
+        "mediaplaypause" | "media_play_pause"    => Media(MediaKeyCode::PlayPause),
+        "xf86audiostop" | "mediastop"            => Media(MediaKeyCode::Stop),
+        "mediareverse"                           => Media(MediaKeyCode::Reverse),
+        "xf86audioforward" | "mediafastforward"  => Media(MediaKeyCode::FastForward),
+        "xf86audiorewind" | "mediarewind"        => Media(MediaKeyCode::Rewind),
+        "xf86audiorecord" | "mediarecord"        => Media(MediaKeyCode::Record),
+        "xf86audionext" | "medianext"
+        | "media_track_next"                     => Media(MediaKeyCode::TrackNext),
+        "xf86audioprev" | "mediaprevious"
+        | "media_track_previous"                 => Media(MediaKeyCode::TrackPrevious),
+        "xf86audioraisevolume" | "volumeup"
+        | "raise_volume"                         => Media(MediaKeyCode::RaiseVolume),
+        "xf86audiolowervolume" | "volumedown"
+        | "lower_volume"                         => Media(MediaKeyCode::LowerVolume),
+        "xf86audiomute" | "volumemute"
+        | "mute_volume"                          => Media(MediaKeyCode::MuteVolume),
+        name => return function_key(name),
+    };
+
+    Some(key_code)
+}
+
+/// Resolves a modifier name to a [ModifierKeyCode]. The super/logo key resolves
+/// to [ModifierKeyCode::LeftMeta], which is how the keyboard widget labels it.
+pub(crate) fn modifier(name: &str) -> Option<ModifierKeyCode> {
+    use ModifierKeyCode::*;
+
+    let modifier = match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" | "ctl"           => LeftControl,
+        "shift"                              => LeftShift,
+        "alt" | "mod1" | "opt" | "option"    => LeftAlt,
+        "super" | "mod4" | "meta" | "win"
+        | "logo" | "cmd" | "command"         => LeftMeta,
+        "hyper" | "mod3"                     => LeftHyper,
+        "altgr" | "mod5"                     => IsoLevel3Shift,
+        "isolevel5shift"                     => IsoLevel5Shift,
+        _                                    => return None,
+    };
+
+    Some(modifier)
+}
+
+/// The name of a key, as understood by [key_code] and [modifier]; the
+/// inverse of both. `None` for keys without a name, e.g. [KeyCode::Null].
+pub(crate) fn name(key_code: KeyCode) -> Option<String> {
+    use KeyCode::*;
+    use ModifierKeyCode::*;
+
+    let name = match key_code {
+        Char(' ')                            => "Space",
+        Char('+')                            => "Plus",
+        Char(c)                              => return Some(c.to_string()),
+        F(n)                                 => return Some(format!("F{n}")),
+        Backspace                            => "Backspace",
+        Enter                                => "Enter",
+        Left                                 => "Left",
+        Right                                => "Right",
+        Up                                   => "Up",
+        Down                                 => "Down",
+        Home                                 => "Home",
+        End                                  => "End",
+        PageUp                               => "PageUp",
+        PageDown                             => "PageDown",
+        Tab                                  => "Tab",
+        BackTab                              => "BackTab",
+        Delete                               => "Delete",
+        Insert                               => "Insert",
+        Esc                                  => "Esc",
+        CapsLock                             => "CapsLock",
+        ScrollLock                           => "ScrollLock",
+        NumLock                              => "NumLock",
+        PrintScreen                          => "Print",
+        Pause                                => "Pause",
+        Menu                                 => "Menu",
+        KeypadBegin                          => "KP_Begin",
+        Media(MediaKeyCode::Play)            => "MediaPlay",
+        Media(MediaKeyCode::Pause)           => "MediaPause",
+        Media(MediaKeyCode::PlayPause)       => "MediaPlayPause",
+        Media(MediaKeyCode::Reverse)         => "MediaReverse",
+        Media(MediaKeyCode::Stop)            => "MediaStop",
+        Media(MediaKeyCode::FastForward)     => "MediaFastForward",
+        Media(MediaKeyCode::Rewind)          => "MediaRewind",
+        Media(MediaKeyCode::TrackNext)       => "MediaNext",
+        Media(MediaKeyCode::TrackPrevious)   => "MediaPrevious",
+        Media(MediaKeyCode::Record)          => "MediaRecord",
+        Media(MediaKeyCode::LowerVolume)     => "VolumeDown",
+        Media(MediaKeyCode::RaiseVolume)     => "VolumeUp",
+        Media(MediaKeyCode::MuteVolume)      => "VolumeMute",
+        Modifier(LeftShift | RightShift)     => "Shift",
+        Modifier(LeftControl | RightControl) => "Ctrl",
+        Modifier(LeftAlt | RightAlt)         => "Alt",
+        Modifier(LeftSuper | RightSuper)     => "Super",
+        Modifier(LeftHyper | RightHyper)     => "Hyper",
+        Modifier(LeftMeta | RightMeta)       => "Meta",
+        Modifier(IsoLevel3Shift)             => "AltGr",
+        Modifier(IsoLevel5Shift)             => "IsoLevel5Shift",
+        Null | Unidentified                  => return None,
+    };
+
+    Some(name.to_string())
+}
+
+/// Splits an uppercase ASCII letter into `Shift` and its lowercase key, for
+/// formats where e.g. `G` means `Shift+g`.
+pub(crate) fn unshifted(key_code: KeyCode) -> Vec<KeyCode> {
+    match key_code {
+        KeyCode::Char(c) if c.is_ascii_uppercase() => vec![
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyCode::Char(c.to_ascii_lowercase()),
+        ],
+        key_code => vec![key_code],
+    }
+}
+
+fn function_key(name: &str) -> Option<KeyCode> {
+    let n: u8 = name.strip_prefix('f')?.parse().ok()?;
+    (1..=35).contains(&n).then_some(KeyCode::F(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_code() {
+        assert_eq!(key_code("Page_Down"), Some(KeyCode::PageDown));
+        assert_eq!(key_code("bracketleft"), Some(KeyCode::Char('[')));
+        assert_eq!(key_code("F11"), Some(KeyCode::F(11)));
+        assert_eq!(key_code("G"), Some(KeyCode::Char('G')));
+        assert_eq!(key_code("XF86AudioMute"), Some(KeyCode::Media(MediaKeyCode::MuteVolume)));
+        assert_eq!(key_code("F0"), None);
+        assert_eq!(key_code("mouse-1"), None);
+    }
+
+    #[test]
+    fn test_name() {
+        let names = ["Space", "Plus", "F11", "KP_Begin", "MediaFastForward", "Ctrl", "AltGr", "g"];
+        for name in names {
+            let key = key_code(name).or_else(|| modifier(name).map(KeyCode::Modifier));
+            assert_eq!(key.and_then(super::name).as_deref(), Some(name));
+        }
+    }
+
+    #[test]
+    fn test_modifier() {
+        assert_eq!(modifier("Mod4"), Some(ModifierKeyCode::LeftMeta));
+        assert_eq!(modifier("CTRL"), Some(ModifierKeyCode::LeftControl));
+        assert_eq!(modifier("q"), None);
+    }
+}
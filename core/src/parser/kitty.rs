@@ -0,0 +1,211 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::{format::any_line_starts_with, key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const DEFAULT_CATEGORY: &str = "kitty";
+
+/// Parses the `map` lines of a `kitty.conf` into a [KeyMap].
+///
+/// Mappings are grouped by the `#: Section {{{` fold markers used by the
+/// default kitty.conf, while mappings for a keyboard mode (`--mode`) are
+/// grouped under the mode's name. Multi-key mappings (`ctrl+a>x`) become
+/// keystroke sequences and `kitty_mod` is expanded to its configured value.
+pub fn parse_kitty_conf(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut section: Option<String> = None;
+    let mut kitty_mod = "ctrl+shift".to_string();
+
+    for line in input.lines().map(str::trim) {
+        if let Some(name) = fold_section(line) {
+            section = Some(name.to_string());
+            continue;
+        } else if line.starts_with("#:") && line.ends_with("}}}") {
+            section = None;
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("kitty_mod") => {
+                if let Some(m) = words.next() { kitty_mod = m.to_string() }
+            },
+            Some("map") => {
+                let Some(mapping) = parse_map(words, &kitty_mod) else { continue };
+                let category = mapping.mode
+                    .or(section.clone())
+                    .unwrap_or(DEFAULT_CATEGORY.to_string());
+
+                actions.entry(category.clone())
+                    .or_default()
+                    .push(Action::new_filter_empty(mapping.action, category, vec![mapping.shortcut]));
+            },
+            _ => (),
+        }
+    }
+
+    KeyMap::new("kitty", actions)
+}
+
+pub struct KittyFormat;
+
+impl KeymapFormat for KittyFormat {
+    fn name(&self) -> &'static str {
+        "kitty"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["kitty.conf", "kitty/*.conf"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        any_line_starts_with(input, "map ") || any_line_starts_with(input, "kitty_mod ")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_kitty_conf(input))
+    }
+}
+
+struct Mapping {
+    mode: Option<String>,
+    shortcut: Shortcut,
+    action: String,
+}
+
+fn parse_map<'a>(mut words: impl Iterator<Item=&'a str>, kitty_mod: &str) -> Option<Mapping> {
+    let mut mode = None;
+    let mut new_mode = None;
+
+    // options, e.g. `--when-focus-on var:in_editor` or `--mode=mw`
+    let keys = loop {
+        let word = words.next()?;
+        let Some(option) = word.strip_prefix("--") else { break word };
+
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None                => (option, words.next()?.to_string()),
+        };
+
+        match name {
+            "mode"     => mode = Some(value),
+            "new-mode" => new_mode = Some(value),
+            _          => (),
+        }
+    };
+
+    let action = match new_mode {
+        Some(new_mode) => format!("enter {} mode", new_mode),
+        None           => words.collect::<Vec<_>>().join(" "),
+    };
+
+    if action.is_empty() || action == "no_op" {
+        return None;
+    }
+
+    let keystrokes = keys.replace("kitty_mod", kitty_mod)
+        .split('>')
+        .map(keystroke)
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Mapping { mode, shortcut: Shortcut::sequence(keystrokes), action })
+}
+
+fn keystroke(keys: &str) -> Option<Vec<KeyCode>> {
+    // a trailing '+' is the plus key itself, e.g. `ctrl++`
+    let (keys, plus) = match keys.strip_suffix("++") {
+        Some(keys) => (keys, Some("+")),
+        None       => (keys, None),
+    };
+
+    let mut names: Vec<&str> = keys.split('+').collect();
+    let key = match plus {
+        Some(plus) => plus,
+        None       => names.pop()?,
+    };
+
+    let mut keystroke = names.into_iter()
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.push(key_code(key)?);
+    Some(keystroke)
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    match name.to_ascii_lowercase().as_str() {
+        "kp_enter"   => Some(KeyCode::Enter),
+        n if n.starts_with("0x") || n.starts_with("kp_") => None,
+        n            => key_names::key_code(n),
+    }
+}
+
+/// The name of a fold section header, e.g. `#: Scrolling {{{`.
+fn fold_section(line: &str) -> Option<&str> {
+    let name = line.strip_prefix("#:")?
+        .trim()
+        .strip_suffix("{{{")?
+        .trim();
+
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_keystroke() {
+        assert_eq!(keystroke("ctrl+shift+page_up"), Some(vec![
+            Modifier(LeftControl), Modifier(LeftShift), PageUp,
+        ]));
+        assert_eq!(keystroke("cmd++"), Some(vec![Modifier(LeftMeta), Char('+')]));
+        assert_eq!(keystroke("ctrl+0x61"), None);
+    }
+
+    #[test]
+    fn test_parse_kitty_conf() {
+        let input = "\
+#: Tab management {{{
+
+map kitty_mod+t     new_tab
+map ctrl+a>c        launch --type=tab --cwd=current
+
+#: }}}
+
+kitty_mod ctrl+alt
+map --when-focus-on var:in_editor kitty_mod+w close_window
+map --new-mode mw kitty_mod+f7
+map --mode mw left  neighboring_window left
+map ctrl+x          no_op
+";
+
+        let keymap = parse_kitty_conf(input);
+
+        assert_categories(&keymap, &[
+            ("Tab management", 2),
+            ("kitty", 2),
+            ("mw", 1),
+        ]);
+
+        let tabs = keymap.actions_by_category("Tab management");
+        assert_eq!(tabs[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftShift), Char('t'),
+        ])]);
+        assert_eq!(tabs[1].name(), "launch --type=tab --cwd=current");
+        assert_eq!(tabs[1].shortcuts(), &[Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('a')],
+            vec![Char('c')],
+        ])]);
+
+        let kitty = keymap.actions_by_category("kitty");
+        assert_eq!(kitty[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftAlt), Char('w'),
+        ])]);
+        assert_eq!(kitty[1].name(), "enter mw mode");
+    }
+}
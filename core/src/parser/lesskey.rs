@@ -0,0 +1,189 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{escape_sequence, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Parses a `lesskey` source file into a [KeyMap], with the `#command` and
+/// `#line-edit` sections as categories. The `#env` section is ignored.
+pub fn parse_lesskey(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut section = Some("command");
+
+    for line in input.lines().map(str::trim) {
+        match line {
+            "#command"   => section = Some("command"),
+            "#line-edit" => section = Some("line-edit"),
+            "#env"       => section = None,
+            "#stop"      => break,
+            l if l.is_empty() || l.starts_with('#') => (),
+            l => {
+                let Some(section) = section else { continue };
+                let Some((shortcut, label)) = binding(l) else { continue };
+
+                actions.entry(section.to_string())
+                    .or_default()
+                    .push(Action::new_filter_empty(label, section.to_string(), vec![shortcut]));
+            },
+        }
+    }
+
+    KeyMap::new("less", actions)
+}
+
+pub struct LesskeyFormat;
+
+impl KeymapFormat for LesskeyFormat {
+    fn name(&self) -> &'static str {
+        "lesskey"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["lesskey", ".lesskey"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.lines()
+            .map(str::trim)
+            .any(|line| matches!(line, "#command" | "#line-edit" | "#env"))
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_lesskey(input))
+    }
+}
+
+/// `^E forw-line` or `\kd forw-line`; an extra string after the action is
+/// kept in the label.
+fn binding(line: &str) -> Option<(Shortcut, String)> {
+    let mut chars = line.chars().peekable();
+
+    let mut keystrokes = Vec::new();
+    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+        keystrokes.extend(match c {
+            '\\' => escape(&mut chars)?,
+            '^'  => {
+                let c = escape_sequence::control(chars.next()?);
+                escape_sequence::keystrokes(&[c])?
+            },
+            c    => escape_sequence::keystrokes(&[c])?,
+        });
+    }
+
+    let action: String = chars.collect();
+    let label = match action.trim() {
+        "" | "invalid" | "noaction" => return None,
+        action                      => action.to_string(),
+    };
+
+    // ESC followed by a key is how less spells alt+key
+    let keystrokes = alt_prefixed(keystrokes);
+    (!keystrokes.is_empty()).then(|| (Shortcut::sequence(keystrokes), label))
+}
+
+/// Folds a lone ESC keystroke into the keystroke that follows it.
+fn alt_prefixed(keystrokes: Vec<Vec<KeyCode>>) -> Vec<Vec<KeyCode>> {
+    let mut folded: Vec<Vec<KeyCode>> = Vec::new();
+    for keystroke in keystrokes {
+        match folded.last_mut() {
+            Some(esc) if esc == &[KeyCode::Esc] => {
+                *esc = std::iter::once(KeyCode::Modifier(ModifierKeyCode::LeftAlt))
+                    .chain(keystroke)
+                    .collect();
+            },
+            _ => folded.push(keystroke),
+        }
+    }
+
+    folded
+}
+
+/// Resolves the escape following a `\`: `\e`, `\n`, `\t`, octal characters
+/// and the `\k` key names.
+fn escape(chars: &mut Peekable<Chars>) -> Option<Vec<Vec<KeyCode>>> {
+    use KeyCode::*;
+
+    let ctrl = |key_code| vec![Modifier(ModifierKeyCode::LeftControl), key_code];
+    let keystroke = match chars.next()? {
+        'e'             => vec![Esc],
+        'b'             => vec![Backspace],
+        'n' | 'r'       => vec![Enter],
+        't'             => vec![Tab],
+        'k'             => match chars.next()? {
+            'b' => vec![Backspace],
+            'B' => ctrl(Backspace),
+            'd' => vec![Down],
+            'D' => vec![PageDown],
+            'e' => vec![End],
+            'h' => vec![Home],
+            'i' => vec![Insert],
+            'l' => vec![Left],
+            'L' => ctrl(Left),
+            'r' => vec![Right],
+            'R' => ctrl(Right),
+            't' => vec![BackTab],
+            'u' => vec![Up],
+            'U' => vec![PageUp],
+            'x' => vec![Delete],
+            'X' => ctrl(Delete),
+            n @ '1'..='9' => vec![F(n as u8 - b'0')],
+            _   => return None,
+        },
+        c @ '0'..='7'   => {
+            let octal: String = std::iter::once(c)
+                .chain(std::iter::from_fn(|| chars.next_if(|c| ('0'..='7').contains(c))).take(2))
+                .collect();
+            let c = char::from_u32(u32::from_str_radix(&octal, 8).ok()?)?;
+            return escape_sequence::keystrokes(&[c]);
+        },
+        c               => return escape_sequence::keystrokes(&[c]),
+    };
+
+    Some(vec![keystroke])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_lesskey() {
+        let input = r#"
+#command
+\r forw-line
+^E forw-line
+G goto-end
+\kD forw-screen
+\ev forw-screen-force
+\40 forw-screen
+x invalid
+\e[ noaction
+#line-edit
+\t forw-complete
+^W word-backspace
+#env
+LESS = -R
+"#;
+
+        let keymap = parse_lesskey(input);
+
+        assert_categories(&keymap, &[
+            ("command", 6),
+            ("line-edit", 2),
+        ]);
+
+        let actions = keymap.actions_by_category("command");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Enter])]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Char('e')])]);
+        assert_eq!(actions[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftShift), Char('g')])]);
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::new(vec![PageDown])]);
+        assert_eq!(actions[4].name(), "forw-screen-force");
+        assert_eq!(actions[4].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Char('v')])]);
+        assert_eq!(actions[5].shortcuts(), &[Shortcut::new(vec![Char(' ')])]);
+    }
+}
@@ -1,4 +1,225 @@
+use crate::key_event::KeyCode;
+use crate::shortcut::Shortcut;
+use std::path::Path;
+
+pub use format::KeymapFormat;
+
+pub mod alacritty;
+pub mod emacs;
+#[cfg(feature = "serde")]
+pub mod exabind;
+pub mod fish;
+pub mod helix;
 pub mod kde;
+pub mod kitty;
+pub mod lesskey;
+pub mod mpv;
+pub mod niri;
+pub mod openbox;
+pub mod qutebrowser;
+pub mod readline;
+pub mod river;
+pub mod sxhkd;
+pub mod wezterm;
+pub mod xbindkeys;
+pub mod zellij;
+pub mod zsh;
+mod escape_sequence;
+mod format;
+mod kdl;
+mod key_names;
+mod shell;
+
+/// All supported keymap formats. When detecting the format of a file, earlier
+/// formats take precedence over later ones.
+pub static FORMATS: &[&dyn KeymapFormat] = &[
+    #[cfg(feature = "serde")]
+    &exabind::ExabindFormat,
+    &kde::KdeFormat,
+    &emacs::EmacsFormat,
+    &openbox::OpenboxFormat,
+    &alacritty::AlacrittyFormat,
+    &helix::HelixFormat,
+    &zellij::ZellijFormat,
+    &niri::NiriFormat,
+    &wezterm::WezTermFormat,
+    &kitty::KittyFormat,
+    &river::RiverFormat,
+    &qutebrowser::QutebrowserFormat,
+    &readline::ReadlineFormat,
+    &zsh::ZshFormat,
+    &fish::FishFormat,
+    &lesskey::LesskeyFormat,
+    &xbindkeys::XbindkeysFormat,
+    &sxhkd::SxhkdFormat,
+    &mpv::MpvFormat,
+];
+
+/// The keymap format with the given name.
+pub fn format(name: &str) -> Option<&'static dyn KeymapFormat> {
+    FORMATS.iter()
+        .copied()
+        .find(|format| format.name() == name)
+}
+
+/// Detects the format of a keymap file from its path and contents.
+///
+/// Formats with a glob matching the path are tried first, and of those, the
+/// first whose contents also look right wins. Failing any glob match, the
+/// contents alone decide.
+pub fn detect_format(path: &Path, input: &str) -> Option<&'static dyn KeymapFormat> {
+    let by_path: Vec<&'static dyn KeymapFormat> = FORMATS.iter()
+        .copied()
+        .filter(|format| format.matches_path(path))
+        .collect();
+
+    by_path.iter()
+        .copied()
+        .find(|format| format.sniff(input))
+        .or_else(|| by_path.first().copied())
+        .or_else(|| FORMATS.iter().copied().find(|format| format.sniff(input)))
+}
+
+/// Parses a shortcut as typed on the command line, e.g. `Meta+Shift+Left` or
+/// `Ctrl+x Ctrl+e` for a sequence. Letters are case-insensitive.
+pub fn parse_shortcut(shortcut: &str) -> Option<Shortcut> {
+    let keystrokes = notation_keystrokes(shortcut)?.into_iter()
+        .map(|keystroke| keystroke.into_iter()
+            .map(|key_code| match key_code {
+                KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+                key_code         => key_code,
+            })
+            .collect())
+        .collect();
+
+    Some(Shortcut::sequence(keystrokes))
+}
+
+/// Parses a shortcut written by [shortcut_notation]; unlike [parse_shortcut],
+/// letters keep their case.
+pub fn parse_notation(shortcut: &str) -> Option<Shortcut> {
+    notation_keystrokes(shortcut).map(Shortcut::sequence)
+}
+
+fn notation_keystrokes(shortcut: &str) -> Option<Vec<Vec<KeyCode>>> {
+    let keystroke = |keystroke: &str| -> Option<Vec<KeyCode>> {
+        let mut names: Vec<&str> = match keystroke.strip_suffix("++") {
+            Some(modifiers) => modifiers.split('+').chain(["+"]).collect(),
+            None            => keystroke.split('+').collect(),
+        };
+
+        let key = key_names::key_code(names.pop()?)?;
+        let mut keystroke = names.into_iter()
+            .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+            .collect::<Option<Vec<_>>>()?;
+
+        keystroke.push(key);
+        Some(keystroke)
+    };
+
+    let keystrokes = shortcut.split_whitespace()
+        .map(keystroke)
+        .collect::<Option<Vec<_>>>()?;
+
+    (!keystrokes.is_empty()).then_some(keystrokes)
+}
+
+/// Writes a shortcut in the notation read by [parse_shortcut], e.g.
+/// `Ctrl+x Ctrl+e`. Keys without a name are left out.
+pub fn shortcut_notation(shortcut: &Shortcut) -> String {
+    shortcut.keystrokes().iter()
+        .map(|keystroke| keystroke.iter()
+            .filter_map(|key_code| key_names::name(*key_code))
+            .collect::<Vec<_>>()
+            .join("+"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Asserts the categories of a parsed keymap, with their number of actions.
+#[cfg(test)]
+pub(crate) fn assert_categories(keymap: &crate::keymap::KeyMap, expected: &[(&str, usize)]) {
+    let expected: Vec<(String, usize)> = expected.iter()
+        .map(|(category, n)| (category.to_string(), *n))
+        .collect();
+
+    assert_eq!(keymap.categories(), expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(path: &str, input: &str) -> Option<&'static str> {
+        detect_format(Path::new(path), input).map(|format| format.name())
+    }
+
+    #[test]
+    fn test_format_names_are_unique() {
+        let mut names: Vec<&str> = FORMATS.iter().map(|format| format.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), FORMATS.len());
+        assert_eq!(format("helix").map(|format| format.name()), Some("helix"));
+        assert!(format("vim").is_none());
+    }
+
+    #[test]
+    fn test_parse_shortcut() {
+        use crate::key_event::ModifierKeyCode::*;
+        use KeyCode::*;
+
+        assert_eq!(parse_shortcut("Meta+Shift+Left"), Some(Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Left,
+        ])));
+        assert_eq!(parse_shortcut("ctrl+A"), Some(Shortcut::new(vec![Modifier(LeftControl), Char('a')])));
+        assert_eq!(parse_shortcut("Ctrl++"), Some(Shortcut::new(vec![Modifier(LeftControl), Char('+')])));
+        assert_eq!(parse_shortcut("Ctrl+x Ctrl+e"), Some(Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('x')],
+            vec![Modifier(LeftControl), Char('e')],
+        ])));
+        assert_eq!(parse_shortcut("Ctrl+Nope"), None);
+        assert_eq!(parse_shortcut(""), None);
+    }
+
+    #[test]
+    fn test_shortcut_notation() {
+        for notation in ["Meta+Shift+Left", "Ctrl+Plus", "Ctrl+x Ctrl+e", "Alt+Space", "F5"] {
+            let shortcut = parse_shortcut(notation).unwrap();
+            assert_eq!(shortcut_notation(&shortcut), notation);
+        }
+    }
+
+    #[test]
+    fn test_detect_format_by_path() {
+        assert_eq!(detected("/home/user/.config/kglobalshortcutsrc", ""), Some("kde"));
+        assert_eq!(detected("/home/user/.config/helix/config.toml", ""), Some("helix"));
+        assert_eq!(detected("/home/user/.config/niri/config.kdl", ""), Some("niri"));
+        assert_eq!(detected("/home/user/.config/zellij/config.kdl", ""), Some("zellij"));
+        assert_eq!(detected("/home/user/.config/mpv/input.conf", ""), Some("mpv"));
+        assert_eq!(detected("/home/user/.inputrc", ""), Some("readline"));
+    }
+
+    #[test]
+    fn test_detect_format_by_contents() {
+        assert_eq!(detected("keys.txt", "bindkey \"^X^E\" edit-command-line"), Some("zsh"));
+        assert_eq!(detected("keys.txt", "bind \\cx edit_command_buffer"), Some("fish"));
+        assert_eq!(detected("keys.txt", "map ctrl+shift+t new_tab"), Some("kitty"));
+        assert_eq!(detected("keys.txt", "riverctl map normal Super Q close"), Some("river"));
+        assert_eq!(detected("keys.txt", "config.bind('J', 'tab-next')"), Some("qutebrowser"));
+        assert_eq!(detected("keys.txt", "\"\\C-x\\C-e\": edit-and-execute-command"), Some("readline"));
+        assert_eq!(detected("keys.txt", "super + Return\n\talacritty"), Some("sxhkd"));
+        assert_eq!(detected("keys.txt", "\"firefox\"\n  Mod4 + f"), Some("xbindkeys"));
+        assert_eq!(detected("keys.txt", "RIGHT seek 5"), Some("mpv"));
+        assert_eq!(detected("keys.txt", "hello"), None);
+    }
 
- 
- 
\ No newline at end of file
+    #[test]
+    fn test_detect_format_prefers_matching_contents() {
+        // a config.kdl under neither niri/ nor zellij/
+        assert_eq!(detected("config.kdl", "binds {\n    Mod+T { spawn \"foot\"; }\n}"), Some("niri"));
+        assert_eq!(detected("config.kdl", "keybinds {\n}"), Some("zellij"));
+        // labwc's rc.xml
+        assert_eq!(detected("/home/user/.config/labwc/rc.xml", "<keybind key=\"W-Return\">"), Some("openbox"));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::key_event::{KeyCode, MediaKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const DEFAULT_CATEGORY: &str = "mpv";
+
+/// Parses an mpv `input.conf` into a [KeyMap], labelling each key with the
+/// command it runs.
+///
+/// Bindings in an input section (`{encode} q quit`) are grouped by section;
+/// key sequences (`g-p`) become keystroke sequences. Mouse buttons and keys
+/// bound to `ignore` are left out.
+pub fn parse_mpv_input_conf(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (category, line) = match line.strip_prefix('{').and_then(|l| l.split_once('}')) {
+            Some((section, line)) => (section.trim(), line.trim_start()),
+            None                  => (DEFAULT_CATEGORY, line),
+        };
+
+        let Some((key, command)) = line.split_once(char::is_whitespace) else { continue };
+        let command = strip_comment(command).trim();
+        if command.is_empty() || command == "ignore" {
+            continue;
+        }
+
+        let Some(keystrokes) = key_sequence(key) else { continue };
+        actions.entry(category.to_string())
+            .or_default()
+            .push(Action::new_filter_empty(command, category, vec![Shortcut::sequence(keystrokes)]));
+    }
+
+    KeyMap::new("mpv", actions)
+}
+
+pub struct MpvFormat;
+
+impl KeymapFormat for MpvFormat {
+    fn name(&self) -> &'static str {
+        "mpv"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["input.conf", "mpv/*.conf"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        const COMMANDS: &[&str] = &[
+            "seek", "cycle", "cycle-values", "add", "set", "multiply", "frame-step",
+            "frame-back-step", "playlist-next", "playlist-prev", "script-binding",
+            "show-text", "quit-watch-later", "screenshot",
+        ];
+
+        input.lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .any(|command| COMMANDS.contains(&command))
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_mpv_input_conf(input))
+    }
+}
+
+/// Removes a trailing `# comment`, ignoring `#` within quotes.
+fn strip_comment(command: &str) -> &str {
+    let mut quote = None;
+    let mut prev = ' ';
+    for (i, c) in command.char_indices() {
+        match (quote, c) {
+            (None, '#') if prev.is_whitespace() => return &command[..i],
+            (None, '"' | '\'')                  => quote = Some(c),
+            (Some(q), c) if q == c              => quote = None,
+            _                                   => (),
+        }
+        prev = c;
+    }
+
+    command
+}
+
+/// Splits a key sequence like `g-p` into keystrokes; a lone `-` is the minus
+/// key.
+fn key_sequence(keys: &str) -> Option<Vec<Vec<KeyCode>>> {
+    if keys.len() > 1 && keys.contains('-') && !keys.ends_with("+-") {
+        keys.split('-').map(keystroke).collect()
+    } else {
+        Some(vec![keystroke(keys)?])
+    }
+}
+
+/// Parses an mpv key, e.g. `Ctrl+RIGHT`, `Shift+PGUP` or `Q`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    // a trailing '+' is the plus key itself, e.g. `Ctrl++`
+    let (key, plus) = match key.strip_suffix("++") {
+        Some(key) => (key, Some("+")),
+        None      => (key, None),
+    };
+
+    let mut names: Vec<&str> = match key {
+        "+" => vec!["+"],
+        key => key.split('+').collect(),
+    };
+
+    let key = match plus {
+        Some(plus) => plus,
+        None       => names.pop()?,
+    };
+
+    let mut keystroke = names.into_iter()
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.extend(key_names::unshifted(key_code(key)?));
+    Some(keystroke)
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    let key_code = match name.to_ascii_uppercase().as_str() {
+        _ if name.chars().count() == 1 => KeyCode::Char(name.chars().next()?),
+        "BS"                           => KeyCode::Backspace,
+        "SHARP"                        => KeyCode::Char('#'),
+        "PGDWN"                        => KeyCode::PageDown,
+        "KP_ENTER"                     => KeyCode::Enter,
+        "PLAYPAUSE"                    => KeyCode::Media(MediaKeyCode::PlayPause),
+        "PLAYONLY"                     => KeyCode::Media(MediaKeyCode::Play),
+        "PAUSEONLY"                    => KeyCode::Media(MediaKeyCode::Pause),
+        "PLAY"                         => KeyCode::Media(MediaKeyCode::Play),
+        "STOP"                         => KeyCode::Media(MediaKeyCode::Stop),
+        "FORWARD"                      => KeyCode::Media(MediaKeyCode::FastForward),
+        "REWIND"                       => KeyCode::Media(MediaKeyCode::Rewind),
+        "NEXT"                         => KeyCode::Media(MediaKeyCode::TrackNext),
+        "PREV"                         => KeyCode::Media(MediaKeyCode::TrackPrevious),
+        "RECORD"                       => KeyCode::Media(MediaKeyCode::Record),
+        "VOLUME_UP"                    => KeyCode::Media(MediaKeyCode::RaiseVolume),
+        "VOLUME_DOWN"                  => KeyCode::Media(MediaKeyCode::LowerVolume),
+        "MUTE"                         => KeyCode::Media(MediaKeyCode::MuteVolume),
+        // mouse buttons, wheel and keypad keys
+        n if n.starts_with("MBTN") || n.starts_with("MOUSE") || n.starts_with("WHEEL") => return None,
+        n if n.starts_with("KP")       => return None,
+        _                              => key_names::key_code(name)?,
+    };
+
+    Some(key_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_mpv_input_conf() {
+        let input = r#"
+# mpv keybindings
+RIGHT seek  5                          # seek 5 seconds forward
+Shift+PGUP seek 600
+Ctrl++ add video-zoom 0.1
+- add volume -2
+Q quit-watch-later
+SPACE cycle pause
+g-p script-binding select/select-playlist
+MBTN_LEFT_DBL cycle fullscreen
+x ignore
+ctrl+h cycle-values hwdec "auto-safe" "no"  # toggle #hwdec
+{encode} q quit 4
+"#;
+
+        let keymap = parse_mpv_input_conf(input);
+
+        assert_categories(&keymap, &[
+            ("encode", 1),
+            ("mpv", 8),
+        ]);
+
+        let actions = keymap.actions_by_category("mpv");
+        assert_eq!(actions[0].name(), "seek  5");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Right])]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftShift), PageUp])]);
+        assert_eq!(actions[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Char('+')])]);
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::new(vec![Char('-')])]);
+        assert_eq!(actions[4].shortcuts(), &[Shortcut::new(vec![Modifier(LeftShift), Char('q')])]);
+        assert_eq!(actions[5].shortcuts(), &[Shortcut::new(vec![Char(' ')])]);
+        assert_eq!(actions[6].shortcuts(), &[Shortcut::sequence(vec![vec![Char('g')], vec![Char('p')]])]);
+        assert_eq!(actions[7].name(), "cycle-values hwdec \"auto-safe\" \"no\"");
+    }
+}
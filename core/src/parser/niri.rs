@@ -0,0 +1,174 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::kdl::{parse_kdl, KdlNode};
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const CATEGORY: &str = "niri";
+
+/// Parses the `binds` block of a niri `config.kdl` into a [KeyMap].
+///
+/// A bind's `hotkey-overlay-title` is used as its label when present,
+/// otherwise its actions are described, e.g. `spawn alacritty`. Binds that
+/// don't repeat (`repeat=false`) or also work on the lock screen
+/// (`allow-when-locked=true`) are marked as such. `Mod` resolves to the
+/// `mod-key` from the `input` block, defaulting to Super.
+pub fn parse_niri_config(input: &str) -> Result<KeyMap, String> {
+    let document = parse_kdl(input)
+        .ok_or("not valid KDL")?;
+
+    let mod_key = document.iter()
+        .filter(|node| node.name == "input")
+        .flat_map(|input| input.children.iter())
+        .filter(|node| node.name == "mod-key")
+        .find_map(|node| node.args.first())
+        .and_then(|key| modifier(key, ModifierKeyCode::LeftMeta))
+        .unwrap_or(ModifierKeyCode::LeftMeta);
+
+    let binds: Vec<Action> = document.iter()
+        .filter(|node| node.name == "binds")
+        .flat_map(|binds| binds.children.iter())
+        .filter_map(|bind| key_binding(bind, mod_key))
+        .collect();
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    if !binds.is_empty() {
+        actions.insert(CATEGORY.to_string(), binds);
+    }
+
+    Ok(KeyMap::new("niri", actions).global())
+}
+
+pub struct NiriFormat;
+
+impl KeymapFormat for NiriFormat {
+    fn name(&self) -> &'static str {
+        "niri"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["niri/config.kdl"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.lines()
+            .filter_map(|line| line.trim_start().strip_prefix("binds"))
+            .any(|rest| rest.trim_start().starts_with('{'))
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_niri_config(input)
+    }
+}
+
+/// `Mod+T repeat=false { spawn "alacritty"; }`
+fn key_binding(bind: &KdlNode, mod_key: ModifierKeyCode) -> Option<Action> {
+    let keystroke = keystroke(&bind.name, mod_key)?;
+
+    let title = bind.prop("hotkey-overlay-title")
+        .filter(|title| *title != "null");
+
+    let mut label = match title {
+        Some(title) => title.to_string(),
+        None        => bind.children.iter()
+            .map(KdlNode::describe)
+            .collect::<Vec<_>>()
+            .join("; "),
+    };
+
+    if label.is_empty() {
+        return None;
+    }
+
+    if bind.prop("repeat") == Some("false") {
+        label.push_str(" (no repeat)");
+    }
+    if bind.prop("allow-when-locked") == Some("true") {
+        label.push_str(" (when locked)");
+    }
+
+    Some(Action::new_filter_empty(label, CATEGORY.to_string(), vec![Shortcut::new(keystroke)]))
+}
+
+/// Parses a niri key, e.g. `Mod+Shift+Slash` or `XF86AudioMute`. Mouse wheel
+/// and touchpad binds are left out.
+fn keystroke(key: &str, mod_key: ModifierKeyCode) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = key.split('+').collect();
+    let key = names.pop()?;
+
+    let mut keystroke = names.into_iter()
+        .map(|m| modifier(m, mod_key).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    // keysyms are matched case-insensitively; shift is spelled out
+    let key_code = match key_names::key_code(key)? {
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        key_code         => key_code,
+    };
+
+    keystroke.push(key_code);
+    Some(keystroke)
+}
+
+fn modifier(name: &str, mod_key: ModifierKeyCode) -> Option<ModifierKeyCode> {
+    match name {
+        "Mod"              => Some(mod_key),
+        "ISO_Level3_Shift" => Some(ModifierKeyCode::IsoLevel3Shift),
+        "ISO_Level5_Shift" => Some(ModifierKeyCode::IsoLevel5Shift),
+        m                  => key_names::modifier(m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_niri_config() {
+        let input = r#"
+input {
+    keyboard { xkb { layout "us"; } }
+}
+
+binds {
+    Mod+Shift+Slash { show-hotkey-overlay; }
+    Mod+T hotkey-overlay-title="Open a Terminal: alacritty" { spawn "alacritty"; }
+    XF86AudioRaiseVolume allow-when-locked=true { spawn "wpctl" "set-volume" "@DEFAULT_AUDIO_SINK@" "0.1+"; }
+    Mod+Q repeat=false { close-window; }
+    Mod+WheelScrollDown cooldown-ms=150 { focus-workspace-down; }
+    Mod+Page_Up { focus-workspace-up; }
+    Ctrl+Alt+Delete { quit; }
+}
+"#;
+
+        let keymap = parse_niri_config(input).unwrap();
+        assert_categories(&keymap, &[("niri", 6)]);
+
+        let actions = keymap.actions_by_category("niri");
+        assert_eq!(actions[0].name(), "show-hotkey-overlay");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Char('/'),
+        ])]);
+        assert_eq!(actions[1].name(), "Open a Terminal: alacritty");
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('t')])]);
+        assert_eq!(actions[2].name(), "spawn wpctl set-volume @DEFAULT_AUDIO_SINK@ 0.1+ (when locked)");
+        assert_eq!(actions[3].name(), "close-window (no repeat)");
+        assert_eq!(actions[4].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), PageUp])]);
+    }
+
+    #[test]
+    fn test_mod_key() {
+        let input = r#"
+input { mod-key "Alt"; }
+binds { Mod+H { focus-column-left; } }
+"#;
+
+        let keymap = parse_niri_config(input).unwrap();
+        let actions = keymap.actions_by_category("niri");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Char('h')])]);
+    }
+}
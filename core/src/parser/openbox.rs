@@ -0,0 +1,220 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+/// Parses the `<keyboard>` section of an Openbox or labwc `rc.xml` into a
+/// [KeyMap].
+///
+/// Nested `<keybind>` chains become keystroke sequences, grouped in a category
+/// named after the chain's root key; all other keybinds share a category named
+/// after the window manager. Labels are formed from the `<action>` names and
+/// their arguments, e.g. "GoToDesktop 2".
+pub fn parse_openbox_rc(input: &str) -> Result<KeyMap, String> {
+    let document = Document::parse(input)
+        .map_err(|e| e.to_string())?;
+
+    let name = match document.root_element().tag_name().name() {
+        "labwc_config" => "labwc",
+        _              => "Openbox",
+    };
+
+    let keybinds = document.descendants()
+        .filter(|node| node.has_tag_name("keyboard"))
+        .flat_map(|keyboard| keyboard.children())
+        .filter(|node| node.has_tag_name("keybind"));
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    for keybind in keybinds {
+        let is_chain = keybind.children().any(|node| node.has_tag_name("keybind"));
+        let category = match keybind.attribute("key") {
+            Some(key) if is_chain => key,
+            _                     => name,
+        };
+
+        collect_actions(category, &[], keybind, &mut actions);
+    }
+
+    Ok(KeyMap::new(name, actions).global())
+}
+
+pub struct OpenboxFormat;
+
+impl KeymapFormat for OpenboxFormat {
+    fn name(&self) -> &'static str {
+        "openbox"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["rc.xml", "*-rc.xml"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.contains("<keybind ")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_openbox_rc(input)
+    }
+}
+
+fn collect_actions(
+    category: &str,
+    prefix: &[Vec<KeyCode>],
+    keybind: Node,
+    actions: &mut HashMap<String, Vec<Action>>,
+) {
+    let Some(keystroke) = keybind.attribute("key").and_then(keystroke) else { return };
+
+    let mut keystrokes = prefix.to_vec();
+    keystrokes.push(keystroke);
+
+    for chained in keybind.children().filter(|node| node.has_tag_name("keybind")) {
+        collect_actions(category, &keystrokes, chained, actions);
+    }
+
+    let label = keybind.children()
+        .filter(|node| node.has_tag_name("action"))
+        .filter_map(action_label)
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    if !label.is_empty() {
+        let shortcut = Shortcut::sequence(keystrokes);
+        actions.entry(category.to_string())
+            .or_default()
+            .push(Action::new_filter_empty(label, category.to_string(), vec![shortcut]));
+    }
+}
+
+/// `<action name="Execute"><command>foot</command></action>` becomes
+/// "Execute foot"; labwc's `<action name="GoToDesktop" to="left" />` becomes
+/// "GoToDesktop left".
+fn action_label(action: Node) -> Option<String> {
+    let name = action.attribute("name")?;
+
+    let attributes = action.attributes()
+        .filter(|attr| attr.name() != "name")
+        .map(|attr| attr.value());
+
+    let arguments = action.descendants()
+        .filter(Node::is_text)
+        .filter_map(|text| text.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+
+    let label = std::iter::once(name)
+        .chain(attributes)
+        .chain(arguments)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(label)
+}
+
+/// Parses an Openbox key, e.g. `W-S-Left` or `C-A-Delete`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = key.split('-').collect();
+    let key = match names.pop()? {
+        // `C--`
+        "" if names.last() == Some(&"") => { names.pop(); "minus" },
+        key => key,
+    };
+
+    let mut keystroke = names.into_iter()
+        .map(|m| modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.extend(key_names::unshifted(key_names::key_code(key)?));
+    Some(keystroke)
+}
+
+fn modifier(name: &str) -> Option<ModifierKeyCode> {
+    match name {
+        "S" => Some(ModifierKeyCode::LeftShift),
+        "C" => Some(ModifierKeyCode::LeftControl),
+        "A" => Some(ModifierKeyCode::LeftAlt),
+        "W" | "M" => Some(ModifierKeyCode::LeftMeta),
+        "H" => Some(ModifierKeyCode::LeftHyper),
+        m   => key_names::modifier(m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_openbox_rc() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<openbox_config xmlns="http://openbox.org/3.4/rc">
+  <keyboard>
+    <chainQuitKey>C-g</chainQuitKey>
+    <keybind key="W-S-Left">
+      <action name="SendToDesktop"><to>left</to></action>
+    </keybind>
+    <keybind key="W-e">
+      <action name="Execute"><command>kate</command></action>
+    </keybind>
+    <keybind key="C-a">
+      <keybind key="1"><action name="GoToDesktop"><to>1</to></action></keybind>
+      <keybind key="C-x">
+        <keybind key="q"><action name="Exit"/></keybind>
+      </keybind>
+    </keybind>
+  </keyboard>
+  <mouse>
+    <context name="Frame">
+      <mousebind button="A-Left" action="Press"><action name="Focus"/></mousebind>
+    </context>
+  </mouse>
+</openbox_config>
+"#;
+
+        let keymap = parse_openbox_rc(input).unwrap();
+
+        assert_categories(&keymap, &[
+            ("C-a", 2),
+            ("Openbox", 2),
+        ]);
+
+        let actions = keymap.actions_by_category("Openbox");
+        assert_eq!(actions[0].name(), "SendToDesktop left");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Left,
+        ])]);
+        assert_eq!(actions[1].name(), "Execute kate");
+
+        let chain = keymap.actions_by_category("C-a");
+        let ctrl = |c| vec![Modifier(LeftControl), Char(c)];
+        assert_eq!(chain[0].name(), "GoToDesktop 1");
+        assert_eq!(chain[0].shortcuts(), &[Shortcut::sequence(vec![ctrl('a'), vec![Char('1')]])]);
+        assert_eq!(chain[1].name(), "Exit");
+        assert_eq!(chain[1].shortcuts(), &[Shortcut::sequence(vec![ctrl('a'), ctrl('x'), vec![Char('q')]])]);
+    }
+
+    #[test]
+    fn test_parse_labwc_rc() {
+        let input = r#"
+<labwc_config>
+  <keyboard>
+    <default />
+    <keybind key="W-Return"><action name="Execute" command="foot" /></keybind>
+    <keybind key="A-Tab"><action name="NextWindow" /></keybind>
+  </keyboard>
+</labwc_config>
+"#;
+
+        let keymap = parse_openbox_rc(input).unwrap();
+        assert_categories(&keymap, &[("labwc", 2)]);
+
+        let actions = keymap.actions_by_category("labwc");
+        assert_eq!(actions[0].name(), "Execute foot");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Enter])]);
+    }
+}
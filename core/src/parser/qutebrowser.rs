@@ -0,0 +1,222 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::{format::any_line_starts_with, key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const DEFAULT_MODE: &str = "normal";
+
+/// Parses qutebrowser key bindings into a [KeyMap], with one category per
+/// mode. Both the `config.bind(...)` calls of a `config.py` and `:bind`
+/// commands (`:bind --mode=insert <Ctrl-e> edit-text`) are understood.
+pub fn parse_qutebrowser_config(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+
+    for line in input.lines().map(str::trim) {
+        let binding = if let Some(args) = line.strip_prefix("config.bind(") {
+            config_bind(args)
+        } else if let Some(args) = line.strip_prefix(":bind ").or(line.strip_prefix("bind ")) {
+            bind_command(args)
+        } else {
+            None
+        };
+
+        let Some((mode, keys, command)) = binding else { continue };
+        let Some(keystrokes) = key_sequence(&keys) else { continue };
+
+        actions.entry(mode.clone())
+            .or_default()
+            .push(Action::new_filter_empty(command, mode, vec![Shortcut::sequence(keystrokes)]));
+    }
+
+    KeyMap::new("qutebrowser", actions)
+}
+
+pub struct QutebrowserFormat;
+
+impl KeymapFormat for QutebrowserFormat {
+    fn name(&self) -> &'static str {
+        "qutebrowser"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["qutebrowser/config.py"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.contains("config.bind(") || any_line_starts_with(input, ":bind ")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_qutebrowser_config(input))
+    }
+}
+
+/// The arguments of `config.bind(keys, command, mode='normal')`.
+fn config_bind(args: &str) -> Option<(String, String, String)> {
+    let mut args = args.trim_start();
+    let mut positional = Vec::new();
+    let mut mode = None;
+
+    while !args.starts_with(')') {
+        let keyword = args.split_once('=')
+            .map(|(name, _)| name.trim())
+            .filter(|name| name.chars().all(|c| c.is_alphanumeric() || c == '_'));
+
+        if let Some(name) = keyword {
+            args = args.split_once('=')?.1.trim_start();
+            let (value, rest) = python_string(args)?;
+            if name == "mode" { mode = Some(value) }
+            args = rest;
+        } else {
+            let (value, rest) = python_string(args)?;
+            positional.push(value);
+            args = rest;
+        }
+
+        args = args.trim_start();
+        args = args.strip_prefix(',').unwrap_or(args).trim_start();
+    }
+
+    let mut positional = positional.into_iter();
+    let keys = positional.next()?;
+    let command = positional.next()?;
+    let mode = mode.or(positional.next()).unwrap_or(DEFAULT_MODE.to_string());
+
+    Some((mode, keys, command))
+}
+
+/// Reads a python string literal, returning its value and the remaining input.
+fn python_string(s: &str) -> Option<(String, &str)> {
+    let s = s.trim_start_matches(['r', 'u']);
+    let quote = s.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+
+    let mut value = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\'            => value.extend(chars.next().map(|(_, c)| c)),
+            c if c == quote => return Some((value, &s[i + 1..])),
+            c               => value.push(c),
+        }
+    }
+
+    None
+}
+
+/// `[--mode=insert] <Ctrl-e> edit-text`
+fn bind_command(args: &str) -> Option<(String, String, String)> {
+    let mut mode = DEFAULT_MODE.to_string();
+    let mut words = args.split_whitespace().peekable();
+
+    while let Some(option) = words.next_if(|w| w.starts_with('-')) {
+        match option.split_once('=') {
+            Some(("--mode" | "-m", m)) => mode = m.to_string(),
+            None if option == "--mode" || option == "-m" => mode = words.next()?.to_string(),
+            _                          => (),
+        }
+    }
+
+    let keys = words.next()?.to_string();
+    let command = words.collect::<Vec<_>>().join(" ");
+    (!command.is_empty()).then_some((mode, keys, command))
+}
+
+/// Splits qutebrowser keys into keystrokes: `gt` is two keystrokes, while
+/// `<Ctrl-Shift-Tab>` is a single keystroke with modifiers.
+fn key_sequence(keys: &str) -> Option<Vec<Vec<KeyCode>>> {
+    let mut keystrokes = Vec::new();
+    let mut rest = keys;
+
+    while let Some(c) = rest.chars().next() {
+        let special = rest.strip_prefix('<')
+            .and_then(|r| r.find('>').filter(|end| *end > 0).map(|end| r.split_at(end)));
+
+        match special {
+            Some((key, after)) => {
+                keystrokes.push(special_key(key)?);
+                rest = &after[1..];
+            },
+            None => {
+                keystrokes.push(key_names::unshifted(KeyCode::Char(c)));
+                rest = &rest[c.len_utf8()..];
+            },
+        }
+    }
+
+    (!keystrokes.is_empty()).then_some(keystrokes)
+}
+
+/// The contents of `<...>`, e.g. `Ctrl-Shift-Tab` or `Escape`.
+fn special_key(key: &str) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = key.split('-').collect();
+    let key = match names.pop()? {
+        // `<Ctrl-->`
+        "" if names.last() == Some(&"") => { names.pop(); "-" },
+        key => key,
+    };
+
+    let mut keystroke = names.into_iter()
+        .map(|m| modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    // letters are case-insensitive within <>; shift is spelled out
+    keystroke.push(match key_names::key_code(key)? {
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        key_code         => key_code,
+    });
+
+    Some(keystroke)
+}
+
+fn modifier(name: &str) -> Option<ModifierKeyCode> {
+    match name.to_ascii_lowercase().as_str() {
+        "mod4" => Some(ModifierKeyCode::LeftMeta),
+        m      => key_names::modifier(m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_qutebrowser_config() {
+        let input = r#"
+config.load_autoconfig(False)
+config.bind('<Ctrl-t>', 'open -t')
+config.bind(',m', 'spawn mpv {url}')
+config.bind("J", 'tab-prev')
+config.bind('<Ctrl-Shift-Tab>', 'tab-prev', mode='normal')
+config.bind('<Ctrl-e>', 'edit-text', 'insert')
+config.unbind('d')
+:bind --mode=insert <Ctrl-h> fake-key <Backspace>
+bind gt tab-next
+"#;
+
+        let keymap = parse_qutebrowser_config(input);
+
+        assert_categories(&keymap, &[
+            ("insert", 2),
+            ("normal", 5),
+        ]);
+
+        let normal = keymap.actions_by_category("normal");
+        assert_eq!(normal[0].name(), "open -t");
+        assert_eq!(normal[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Char('t')])]);
+        assert_eq!(normal[1].name(), "spawn mpv {url}");
+        assert_eq!(normal[1].shortcuts(), &[Shortcut::sequence(vec![vec![Char(',')], vec![Char('m')]])]);
+        assert_eq!(normal[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftShift), Char('j')])]);
+        assert_eq!(normal[3].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftShift), Tab,
+        ])]);
+        assert_eq!(normal[4].shortcuts(), &[Shortcut::sequence(vec![vec![Char('g')], vec![Char('t')]])]);
+
+        let insert = keymap.actions_by_category("insert");
+        assert_eq!(insert[0].name(), "edit-text");
+        assert_eq!(insert[1].name(), "fake-key <Backspace>");
+    }
+}
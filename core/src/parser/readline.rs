@@ -0,0 +1,195 @@
+use crate::keymap::KeyMap;
+use crate::parser::{escape_sequence, format::any_line_starts_with, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const DEFAULT_CATEGORY: &str = "readline";
+
+/// Parses readline key bindings into a [KeyMap], either from an `.inputrc`
+/// or from the output of `bind -p` (and `bind -s` for macros).
+///
+/// Bindings are grouped by readline keymap, as selected by `set keymap`,
+/// `set editing-mode` or a `$if mode=...` block. Functions bound to every
+/// printable character, such as `self-insert`, are left out.
+pub fn parse_inputrc(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut category = DEFAULT_CATEGORY.to_string();
+    let mut conditionals: Vec<String> = Vec::new();
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("$if"), Some(test), _) => {
+                conditionals.push(category.clone());
+                if let Some(mode) = test.strip_prefix("mode=") {
+                    category = keymap_name(mode);
+                }
+            },
+            (Some("$endif"), _, _) => {
+                if let Some(outer) = conditionals.pop() { category = outer }
+            },
+            (Some("set"), Some("keymap" | "editing-mode"), Some(keymap)) => {
+                category = keymap_name(keymap);
+            },
+            _ if line.starts_with('$') || line.starts_with("set ") => (),
+            _ => {
+                let Some((shortcut, label)) = binding(line) else { continue };
+                actions.entry(category.clone())
+                    .or_default()
+                    .push(Action::new_filter_empty(label, category.clone(), vec![shortcut]));
+            },
+        }
+    }
+
+    KeyMap::new("Readline", actions)
+}
+
+pub struct ReadlineFormat;
+
+impl KeymapFormat for ReadlineFormat {
+    fn name(&self) -> &'static str {
+        "readline"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &[".inputrc", "inputrc", "*.inputrc"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        ["\"\\C-", "\"\\M-", "\"\\e", "set editing-mode", "set keymap", "$if mode="].iter()
+            .any(|prefix| any_line_starts_with(input, prefix))
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_inputrc(input))
+    }
+}
+
+/// Resolves keymap aliases, so that e.g. `set editing-mode vi` and
+/// `set keymap vi-insert` end up in the same category.
+fn keymap_name(keymap: &str) -> String {
+    match keymap {
+        "emacs-standard"   => "emacs",
+        "vi"               => "vi-insert",
+        "vi-move"          => "vi-command",
+        keymap             => keymap,
+    }.to_string()
+}
+
+/// `"\C-x\C-e": edit-and-execute-command`, `Meta-Rubout: backward-kill-word`
+/// or a macro, `"\C-xq": "\C-a\C-k"`.
+fn binding(line: &str) -> Option<(Shortcut, String)> {
+    let (seq, target) = if line.starts_with('"') {
+        let (seq, rest) = key_sequence(line)?;
+        (seq, rest.trim_start().strip_prefix(':')?)
+    } else {
+        let (key_name, target) = line.split_once(':')?;
+        (key_name_sequence(key_name.trim())?, target)
+    };
+
+    let label = match target.trim() {
+        "" | "self-insert" | "do-lowercase-version" => return None,
+        macro_ if macro_.starts_with(['"', '\'']) => format!("send {macro_}"),
+        function => function.to_string(),
+    };
+
+    let keystrokes = escape_sequence::keystrokes(&seq)?;
+    Some((Shortcut::sequence(keystrokes), label))
+}
+
+/// Splits a line starting with a quoted key sequence into the unescaped
+/// sequence and the remainder after the closing `"`.
+fn key_sequence(line: &str) -> Option<(Vec<char>, &str)> {
+    let mut escaped = false;
+    let (end, _) = line.char_indices().skip(1).find(|(_, c)| {
+        let closing = *c == '"' && !escaped;
+        escaped = *c == '\\' && !escaped;
+        closing
+    })?;
+
+    Some((escape_sequence::unescape(&line[1..end], false)?, &line[end + 1..]))
+}
+
+/// An unquoted key name, e.g. `Control-u` or `M-DEL`.
+fn key_name_sequence(key_name: &str) -> Option<Vec<char>> {
+    let mut parts: Vec<&str> = key_name.split('-').collect();
+    let key = match parts.pop()? {
+        // `C--`
+        "" => "-",
+        key => key,
+    };
+
+    let mut c = match key.to_ascii_uppercase().as_str() {
+        "DEL" | "RUBOUT"    => '\x7f',
+        "ESC" | "ESCAPE"    => '\x1b',
+        "LFD" | "NEWLINE"   => '\n',
+        "RET" | "RETURN"    => '\r',
+        "SPACE" | "SPC"     => ' ',
+        "TAB"               => '\t',
+        _ if key.chars().count() == 1 => key.chars().next()?,
+        _                   => return None,
+    };
+
+    let mut meta = false;
+    for modifier in parts.into_iter().filter(|m| !m.is_empty()) {
+        match modifier.to_ascii_lowercase().as_str() {
+            "c" | "control" => c = escape_sequence::control(c),
+            "m" | "meta"    => meta = true,
+            _               => return None,
+        }
+    }
+
+    Some(if meta { vec!['\x1b', c] } else { vec![c] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+
+    #[test]
+    fn test_parse_inputrc() {
+        let input = r#"
+# alias-expand-line (not bound)
+"\C-g": abort
+"\C-x\C-e": edit-and-execute-command
+"\e[1;5C": forward-word
+"\M-\C-h": backward-kill-word
+"a": self-insert
+Meta-Rubout: backward-kill-word
+"\C-xq": "\C-a\C-k"
+
+$if mode=vi
+set keymap vi-command
+"\C-l": clear-screen
+$endif
+"#;
+
+        let keymap = parse_inputrc(input);
+
+        assert_categories(&keymap, &[
+            ("readline", 6),
+            ("vi-command", 1),
+        ]);
+
+        let actions = keymap.actions_by_category("readline");
+        assert_eq!(actions[0].name(), "abort");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Char('g')])]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('x')],
+            vec![Modifier(LeftControl), Char('e')],
+        ])]);
+        assert_eq!(actions[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Right])]);
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftAlt), Char('h'),
+        ])]);
+        assert_eq!(actions[4].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Backspace])]);
+        assert_eq!(actions[5].name(), r#"send "\C-a\C-k""#);
+    }
+}
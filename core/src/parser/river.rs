@@ -0,0 +1,239 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::shell::{logical_lines, posix_commands};
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+/// Parses the `riverctl map` commands of a river `init` script into a
+/// [KeyMap], with one category per mode.
+///
+/// Simple shell loops, such as `for mode in normal locked` or
+/// `for i in $(seq 1 9)`, are expanded, also when written on one line with
+/// `;`; other shell constructs are ignored.
+pub fn parse_river_init(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    // the enclosing `for` loops, and `None` for `while` and `until` loops
+    let mut loops: Vec<Option<(String, Vec<String>)>> = Vec::new();
+
+    for line in logical_lines(input) {
+        for words in posix_commands(&line) {
+            let words = match words.split_first() {
+                Some((first, rest)) if first == "do" => rest,
+                _                                    => &words[..],
+            };
+
+            match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                ["for", var, "in", values @ ..] => {
+                    loops.push(Some((var.to_string(), loop_values(values))));
+                },
+                ["for" | "while" | "until", ..] => {
+                    loops.push(None);
+                },
+                ["done", ..] => {
+                    loops.pop();
+                },
+                ["riverctl", "map", ..] => {
+                    for words in expand_variables(words, &loops) {
+                        if let Some((mode, action)) = map(&words[2..]) {
+                            actions.entry(mode).or_default().push(action);
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    KeyMap::new("river", actions).global()
+}
+
+pub struct RiverFormat;
+
+impl KeymapFormat for RiverFormat {
+    fn name(&self) -> &'static str {
+        "river"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["river/init"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.contains("riverctl map")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_river_init(input))
+    }
+}
+
+/// `riverctl map [-release|-repeat|-layout index] mode modifiers key command`
+fn map(args: &[String]) -> Option<(String, Action)> {
+    let mut args = args.iter();
+
+    let mode = loop {
+        match args.next()?.as_str() {
+            "-layout"                => { args.next()?; },
+            "-release" | "-repeat"   => (),
+            mode                     => break mode.to_string(),
+        }
+    };
+
+    let modifiers = args.next()?;
+    let key = args.next()?;
+    let command = args.map(String::as_str).collect::<Vec<_>>().join(" ");
+    if command.is_empty() {
+        return None;
+    }
+
+    let mut keystroke = modifiers.split('+')
+        .filter(|m| *m != "None")
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    // keysyms are matched case-insensitively; shift is spelled out
+    keystroke.push(match key_names::key_code(key)? {
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        key_code         => key_code,
+    });
+
+    let action = Action::new_filter_empty(command, mode.clone(), vec![Shortcut::new(keystroke)]);
+    Some((mode, action))
+}
+
+/// The values iterated over by a `for` loop: plain words, or `$(seq 1 9)`.
+fn loop_values(values: &[&str]) -> Vec<String> {
+    match values {
+        ["$(seq", from, to] => {
+            let from: u32 = from.parse().unwrap_or(1);
+            let to: u32 = to.trim_end_matches(')').parse().unwrap_or(0);
+            (from..=to).map(|i| i.to_string()).collect()
+        },
+        values => values.iter().map(|v| v.to_string()).collect(),
+    }
+}
+
+/// Substitutes `$var` and `${var}` for the variables of all enclosing loops,
+/// producing one command per combination of loop values.
+fn expand_variables(words: &[String], loops: &[Option<(String, Vec<String>)>]) -> Vec<Vec<String>> {
+    loops.iter().flatten().fold(vec![words.to_vec()], |commands, (var, values)| {
+        commands.into_iter()
+            .flat_map(|words| {
+                if !words.iter().any(|w| substitute(w, var, "").is_some()) {
+                    return vec![words];
+                }
+
+                values.iter()
+                    .map(|value| words.iter()
+                        .map(|w| substitute(w, var, value).unwrap_or_else(|| w.clone()))
+                        .collect())
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+/// The word with `${var}` and `$var` replaced by the value, or `None` if it
+/// doesn't refer to the variable. `$var` must not continue into a longer
+/// name: `$index` doesn't refer to `i`.
+fn substitute(word: &str, var: &str, value: &str) -> Option<String> {
+    let braced = format!("${{{var}}}");
+    let plain = format!("${var}");
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let mut substituted = String::new();
+    let mut found = false;
+    let mut rest = word;
+    while let Some(i) = rest.find('$') {
+        substituted.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let tail = rest.strip_prefix(braced.as_str())
+            .or_else(|| rest.strip_prefix(plain.as_str()).filter(|tail| !tail.starts_with(is_name_char)));
+
+        match tail {
+            Some(tail) => {
+                substituted.push_str(value);
+                rest = tail;
+                found = true;
+            },
+            None => {
+                substituted.push('$');
+                rest = &rest[1..];
+            },
+        }
+    }
+
+    substituted.push_str(rest);
+    found.then_some(substituted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::key_event::MediaKeyCode;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_river_init() {
+        let input = r#"
+#!/bin/sh
+
+# Super+Shift+Return to start an instance of foot
+riverctl map normal Super+Shift Return spawn foot
+riverctl map normal Super Q close
+riverctl map -repeat normal Super J focus-view next
+
+for i in $(seq 1 3)
+do
+    tags=$((1 << ($i - 1)))
+    riverctl map normal Super $i set-focused-tags $tags
+    riverctl map normal Super+Shift ${i} spawn "foo $index"
+done
+
+riverctl declare-mode passthrough
+riverctl map normal Super F11 enter-mode passthrough
+riverctl map passthrough Super F11 enter-mode normal
+
+for mode in normal locked
+do
+    while false; do :; done
+    riverctl map $mode None XF86AudioRaiseVolume \
+        spawn 'pamixer -i 5'
+done
+
+for mode in normal locked; do riverctl map $mode None XF86AudioMute spawn mute; done
+riverctl map locked None XF86AudioPlay spawn 'playerctl play-pause'
+"#;
+
+        let keymap = parse_river_init(input);
+
+        assert_categories(&keymap, &[
+            ("locked", 3),
+            ("normal", 12),
+            ("passthrough", 1),
+        ]);
+
+        let normal = keymap.actions_by_category("normal");
+        assert_eq!(normal[0].name(), "spawn foot");
+        assert_eq!(normal[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Enter,
+        ])]);
+        assert_eq!(normal[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('j')])]);
+        assert_eq!(normal[4].name(), "set-focused-tags $tags");
+        assert_eq!(normal[4].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('2')])]);
+        assert_eq!(normal[6].name(), "spawn foo $index");
+        assert_eq!(normal[6].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Char('1'),
+        ])]);
+
+        let locked = keymap.actions_by_category("locked");
+        assert_eq!(locked[0].name(), "spawn pamixer -i 5");
+        assert_eq!(locked[0].shortcuts(), &[Shortcut::new(vec![Media(MediaKeyCode::RaiseVolume)])]);
+        assert_eq!(locked[1].name(), "spawn mute");
+        assert_eq!(locked[2].name(), "spawn playerctl play-pause");
+    }
+}
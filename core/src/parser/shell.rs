@@ -0,0 +1,141 @@
+//! Line joining and word splitting for shell scripts and the configs that
+//! borrow shell syntax. Each parser decides what a backslash escape means.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A shell word with its quotes removed; `raw` records whether it was spelled
+/// without quotes or escapes.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Word {
+    pub text: String,
+    pub raw: bool,
+}
+
+/// Joins lines ending with a `\` with the line that follows; the leading
+/// whitespace of a continuation is dropped, that of the first line is kept.
+pub(crate) fn logical_lines(input: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut continued: Option<String> = None;
+
+    for line in input.lines() {
+        let line = match continued.take() {
+            Some(head) => head + line.trim_start(),
+            None       => line.to_string(),
+        };
+
+        match line.strip_suffix('\\') {
+            Some(head) => continued = Some(head.to_string()),
+            None       => lines.push(line),
+        }
+    }
+
+    lines.extend(continued);
+    lines
+}
+
+/// Splits a line into words, removing quotes. A `#` starting a word comments
+/// out the rest of the line if `comments` is set.
+///
+/// `escape` turns a backslash escape into text, given the quote it appears
+/// in; the escaped characters are still in `chars`. Returns `None` if an
+/// escape is rejected or a quote is left open.
+pub(crate) fn words<F>(line: &str, comments: bool, escape: F) -> Option<Vec<Word>>
+where
+    F: FnMut(Option<char>, &mut Peekable<Chars>) -> Option<String>,
+{
+    let (words, open_quote) = split(line, comments, escape)?;
+    open_quote.is_none().then_some(words)
+}
+
+/// Splits a line into commands at unquoted `;`s, and those into word texts
+/// like a POSIX shell: a backslash escapes the next character, except in
+/// single quotes. An open quote runs to the end of the line.
+pub(crate) fn posix_commands(line: &str) -> Vec<Vec<String>> {
+    let escape = |quote, chars: &mut Peekable<Chars>| match quote {
+        Some('\'') => Some("\\".to_string()),
+        _          => Some(chars.next().map(String::from).unwrap_or_default()),
+    };
+
+    let words = split(line, true, escape)
+        .map(|(words, _)| words)
+        .unwrap_or_default();
+
+    let mut commands: Vec<Vec<String>> = vec![Vec::new()];
+    for word in words {
+        if !word.raw {
+            commands.last_mut().expect("at least one command").push(word.text);
+            continue;
+        }
+
+        for (i, text) in word.text.split(';').enumerate() {
+            if i > 0 {
+                commands.push(Vec::new());
+            }
+            if !text.is_empty() {
+                commands.last_mut().expect("at least one command").push(text.to_string());
+            }
+        }
+    }
+
+    commands.retain(|command| !command.is_empty());
+    commands
+}
+
+/// The words of a line and the quote left open at its end, if any.
+fn split<F>(line: &str, comments: bool, mut escape: F) -> Option<(Vec<Word>, Option<char>)>
+where
+    F: FnMut(Option<char>, &mut Peekable<Chars>) -> Option<String>,
+{
+    let mut words = Vec::new();
+    let mut word: Option<Word> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    let new_word = || Word { text: String::new(), raw: true };
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace()            => words.extend(word.take()),
+            (None, '#') if comments && word.is_none() => break,
+            (None, '"' | '\'')                        => {
+                quote = Some(c);
+                word.get_or_insert_with(new_word).raw = false;
+            },
+            (Some(q), c) if q == c                    => quote = None,
+            (_, '\\')                                 => {
+                let escaped = escape(quote, &mut chars)?;
+                let word = word.get_or_insert_with(new_word);
+                word.raw &= quote.is_some();
+                word.text.push_str(&escaped);
+            },
+            (_, c)                                    => word.get_or_insert_with(new_word).text.push(c),
+        }
+    }
+
+    words.extend(word);
+    Some((words, quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logical_lines() {
+        let input = "a \\\n    b\n  c\nd \\";
+        assert_eq!(logical_lines(input), vec!["a b", "  c", "d "]);
+    }
+
+    #[test]
+    fn test_posix_commands() {
+        assert_eq!(posix_commands(r#"spawn "foo bar" 'a\b' c\ d # comment"#),
+            vec![vec!["spawn", "foo bar", r"a\b", "c d"]]);
+        assert_eq!(posix_commands(r#"a "b c"#), vec![vec!["a", "b c"]]);
+        assert_eq!(posix_commands("for i in 1 2 ;do echo 'x;' $i; done"), vec![
+            vec!["for", "i", "in", "1", "2"],
+            vec!["do", "echo", "x;", "$i"],
+            vec!["done"],
+        ]);
+    }
+}
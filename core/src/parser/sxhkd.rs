@@ -0,0 +1,235 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::parser::shell::logical_lines;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const DEFAULT_CATEGORY: &str = "sxhkd";
+
+/// Parses an `sxhkdrc` into a [KeyMap], labelling each hotkey with the command
+/// it runs.
+///
+/// Brace sequences, e.g. `super + {_,shift + }{1-9}`, are expanded into one
+/// action per hotkey, paired with the matching expansion of the command. Chord
+/// chains (`super + a ; b`) become keystroke sequences. Hotkeys are grouped by
+/// the section headers of the bspwm example config:
+///
+/// ```text
+/// #
+/// # wm independent hotkeys
+/// #
+/// ```
+pub fn parse_sxhkdrc(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut category = DEFAULT_CATEGORY.to_string();
+    let mut hotkey: Option<String> = None;
+    let mut after_rule = false;
+
+    for line in logical_lines(input) {
+        let trimmed = line.trim();
+
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            let comment = comment.trim();
+            if after_rule && !comment.is_empty() {
+                category = comment.to_string();
+            }
+            after_rule = comment.is_empty();
+            continue;
+        }
+
+        after_rule = false;
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) {
+            hotkey = Some(trimmed.to_string());
+        } else if let Some(hotkey) = hotkey.take() {
+            actions.entry(category.clone())
+                .or_default()
+                .extend(hotkey_actions(&category, &hotkey, trimmed));
+        }
+    }
+
+    actions.retain(|_, actions| !actions.is_empty());
+    KeyMap::new("sxhkd", actions).global()
+}
+
+pub struct SxhkdFormat;
+
+impl KeymapFormat for SxhkdFormat {
+    fn name(&self) -> &'static str {
+        "sxhkd"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["sxhkdrc"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        // a hotkey, followed by its indented command
+        input.lines()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|lines| {
+                let (hotkey, command) = (lines[0], lines[1]);
+                !hotkey.starts_with(['#', ' ', '\t'])
+                    && hotkey.contains(" + ")
+                    && command.starts_with(char::is_whitespace)
+                    && !command.trim().is_empty()
+            })
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_sxhkdrc(input))
+    }
+}
+
+fn hotkey_actions(category: &str, hotkey: &str, command: &str) -> Vec<Action> {
+    let hotkeys = expand(hotkey);
+    let commands = expand(command);
+
+    hotkeys.iter()
+        .enumerate()
+        .filter_map(|(i, hotkey)| {
+            // sxhkd requires the expansions to line up; fall back to the
+            // unexpanded command if they don't
+            let command = match commands.len() {
+                1                          => &commands[0],
+                n if n == hotkeys.len()    => &commands[i],
+                _                          => command,
+            };
+
+            let keystrokes = hotkey.split([';', ':'])
+                .map(chord)
+                .collect::<Option<Vec<_>>>()?;
+
+            let shortcut = Shortcut::sequence(keystrokes);
+            Some(Action::new_filter_empty(command.trim().to_string(), category.to_string(), vec![shortcut]))
+        })
+        .collect()
+}
+
+/// Expands brace sequences into every combination, left to right. Within a
+/// sequence, `_` is the empty element and `a-z` or `1-9` a range.
+fn expand(s: &str) -> Vec<String> {
+    let Some(start) = s.find('{') else { return vec![s.to_string()] };
+    let Some(len) = s[start..].find('}') else { return vec![s.to_string()] };
+
+    let head = &s[..start];
+    let tails = expand(&s[start + len + 1..]);
+
+    s[start + 1..start + len].split(',')
+        .flat_map(elements)
+        .flat_map(|element| tails.iter().map(move |tail| format!("{head}{element}{tail}")))
+        .collect()
+}
+
+fn elements(element: &str) -> Vec<String> {
+    let chars: Vec<char> = element.chars().collect();
+    match chars.as_slice() {
+        ['_']                                  => vec![String::new()],
+        [from, '-', to] if from.is_ascii_alphanumeric() && from < to
+                                               => (*from..=*to).map(String::from).collect(),
+        _                                      => vec![element.to_string()],
+    }
+}
+
+/// Parses a chord, e.g. `super + shift + Return` or `@space`.
+fn chord(chord: &str) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = chord.split('+').map(str::trim).collect();
+
+    // `@` triggers on release and `~` replays the event, neither affects the key
+    let key = names.pop()?.trim_start_matches(['@', '~']);
+
+    let mut keystroke = names.into_iter()
+        .map(|m| modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.extend(key_names::unshifted(key_names::key_code(key)?));
+    Some(keystroke)
+}
+
+fn modifier(name: &str) -> Option<ModifierKeyCode> {
+    match name {
+        "mode_switch" => Some(ModifierKeyCode::IsoLevel3Shift),
+        m             => key_names::modifier(m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::assert_categories;
+    use ModifierKeyCode::*;
+    use KeyCode::*;
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("super + {_,shift + }{1-3}"), vec![
+            "super + 1", "super + 2", "super + 3",
+            "super + shift + 1", "super + shift + 2", "super + shift + 3",
+        ]);
+        assert_eq!(expand("bspc node -z {left -20 0,right 20 0}"), vec![
+            "bspc node -z left -20 0",
+            "bspc node -z right 20 0",
+        ]);
+        assert_eq!(expand("super + Return"), vec!["super + Return"]);
+    }
+
+    #[test]
+    fn test_parse_sxhkdrc() {
+        let input = r#"
+#
+# wm independent hotkeys
+#
+
+# terminal emulator
+super + Return
+	alacritty
+
+super + @space
+	rofi -show run
+
+#
+# state/flags
+#
+
+super + {_,shift + }{1-2}
+	bspc {desktop -f,node -d} '^{1-2}'
+
+super + a ; {h,l}
+	bspc node -f {west,east}
+
+super + alt + Escape
+	bspc quit && \
+	pkill sxhkd
+"#;
+
+        let keymap = parse_sxhkdrc(input);
+
+        assert_categories(&keymap, &[
+            ("state/flags", 7),
+            ("wm independent hotkeys", 2),
+        ]);
+
+        let actions = keymap.actions_by_category("wm independent hotkeys");
+        assert_eq!(actions[0].name(), "alacritty");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Enter])]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char(' ')])]);
+
+        let actions = keymap.actions_by_category("state/flags");
+        assert_eq!(actions[0].name(), "bspc desktop -f '^1'");
+        assert_eq!(actions[3].name(), "bspc node -d '^2'");
+        assert_eq!(actions[3].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftMeta), Modifier(LeftShift), Char('2'),
+        ])]);
+        assert_eq!(actions[5].name(), "bspc node -f east");
+        assert_eq!(actions[5].shortcuts(), &[Shortcut::sequence(vec![
+            vec![Modifier(LeftMeta), Char('a')],
+            vec![Char('l')],
+        ])]);
+        assert_eq!(actions[6].name(), "bspc quit && pkill sxhkd");
+    }
+}
@@ -0,0 +1,481 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use lua::LuaValue;
+use std::collections::HashMap;
+
+const DEFAULT_CATEGORY: &str = "WezTerm";
+
+/// Parses the static `keys = { ... }` and `key_tables = { ... }` tables of a
+/// `wezterm.lua` into a [KeyMap].
+///
+/// Only table literals are understood; bindings built at runtime (loops,
+/// `table.insert`) are not evaluated. Each key table becomes its own category,
+/// and `LEADER` bindings become sequences starting with the `leader` key.
+pub fn parse_wezterm_lua(input: &str) -> KeyMap {
+    let tables = lua::assigned_tables(input, &["keys", "key_tables", "leader"]);
+
+    let leader = tables.iter()
+        .find(|(name, _)| *name == "leader")
+        .and_then(|(_, leader)| keystroke(leader, None));
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    let mut add_bindings = |category: &str, bindings: &LuaValue| {
+        let bindings = bindings.entries()
+            .filter_map(|(_, binding)| key_binding(category, binding, leader.as_ref()));
+
+        actions.entry(category.to_string())
+            .or_default()
+            .extend(bindings);
+    };
+
+    for (name, table) in &tables {
+        match *name {
+            "keys"       => add_bindings(DEFAULT_CATEGORY, table),
+            "key_tables" => table.entries()
+                .filter_map(|(name, bindings)| Some((name?, bindings)))
+                .for_each(|(name, bindings)| add_bindings(name, bindings)),
+            _            => (),
+        }
+    }
+
+    actions.retain(|_, actions| !actions.is_empty());
+    KeyMap::new("WezTerm", actions)
+}
+
+pub struct WezTermFormat;
+
+impl KeymapFormat for WezTermFormat {
+    fn name(&self) -> &'static str {
+        "wezterm"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["wezterm.lua", ".wezterm.lua", "wezterm/*.lua"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        input.contains("require") && input.contains("wezterm")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_wezterm_lua(input))
+    }
+}
+
+fn key_binding(category: &str, binding: &LuaValue, leader: Option<&Vec<KeyCode>>) -> Option<Action> {
+    let action = binding.get("action")?.text();
+    let action = action.strip_prefix("wezterm.action.")
+        .or_else(|| action.strip_prefix("act."))
+        .unwrap_or(action.as_str());
+
+    if action == "DisableDefaultAssignment" || action == "Nop" {
+        return None;
+    }
+
+    let shortcut = match keystroke(binding, leader) {
+        Some(keystroke) if uses_leader(binding) => Shortcut::sequence(vec![leader?.clone(), keystroke]),
+        Some(keystroke)                         => Shortcut::new(keystroke),
+        None                                    => return None,
+    };
+
+    Some(Action::new_filter_empty(action, category, vec![shortcut]))
+}
+
+fn uses_leader(binding: &LuaValue) -> bool {
+    binding.get("mods")
+        .map(|mods| mods.text().split('|').any(|m| m.trim() == "LEADER"))
+        .unwrap_or(false)
+}
+
+fn keystroke(binding: &LuaValue, leader: Option<&Vec<KeyCode>>) -> Option<Vec<KeyCode>> {
+    let key = binding.get("key")?.text();
+    let mods = binding.get("mods").map(LuaValue::text).unwrap_or_default();
+
+    let mut keystroke = mods.split(['|', '+'])
+        .map(str::trim)
+        .filter(|m| !m.is_empty() && *m != "NONE")
+        .filter(|m| *m != "LEADER" || leader.is_none())
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.push(key_code(&key)?);
+    Some(keystroke)
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    // physical and mapped key prefixes don't matter for the layout
+    let name = name.strip_prefix("phys:")
+        .or_else(|| name.strip_prefix("mapped:"))
+        .unwrap_or(name);
+
+    let key_code = match name {
+        "LeftArrow"  => KeyCode::Left,
+        "RightArrow" => KeyCode::Right,
+        "UpArrow"    => KeyCode::Up,
+        "DownArrow"  => KeyCode::Down,
+        n if n.starts_with("raw:") => return None,
+        n => match key_names::key_code(n)? {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            key_code         => key_code,
+        },
+    };
+
+    Some(key_code)
+}
+
+/// Just enough Lua to read table literals.
+mod lua {
+    /// A Lua value: strings and tables are parsed, everything else (function
+    /// calls, identifiers, numbers) is kept as source text.
+    #[derive(Debug, Clone, PartialEq)]
+    pub(super) enum LuaValue<'a> {
+        Str(String),
+        Table(Vec<(Option<&'a str>, LuaValue<'a>)>),
+        Expr(&'a str),
+    }
+
+    impl<'a> LuaValue<'a> {
+        pub(super) fn get(&self, name: &str) -> Option<&LuaValue<'a>> {
+            self.entries()
+                .find(|(key, _)| *key == Some(name))
+                .map(|(_, value)| value)
+        }
+
+        pub(super) fn entries(&self) -> impl Iterator<Item=(Option<&'a str>, &LuaValue<'a>)> {
+            let entries = match self {
+                LuaValue::Table(entries) => entries.as_slice(),
+                _                        => &[],
+            };
+
+            entries.iter().map(|(key, value)| (*key, value))
+        }
+
+        /// The value as text; expressions have their whitespace collapsed.
+        pub(super) fn text(&self) -> String {
+            match self {
+                LuaValue::Str(s)   => s.clone(),
+                LuaValue::Expr(e)  => e.split_whitespace().collect::<Vec<_>>().join(" "),
+                LuaValue::Table(_) => String::new(),
+            }
+        }
+    }
+
+    /// Finds all `name = { ... }` assignments of the given names, including
+    /// fields like `config.keys = { ... }` and nested `keys = { ... }`.
+    pub(super) fn assigned_tables<'a>(src: &'a str, names: &[&str]) -> Vec<(&'a str, LuaValue<'a>)> {
+        let mut parser = Parser { src, pos: 0 };
+        let mut tables = Vec::new();
+
+        while parser.skip_whitespace() && parser.pos < src.len() {
+            if let Some(ident) = parser.identifier() {
+                if !names.contains(&ident) {
+                    continue;
+                }
+
+                let checkpoint = parser.pos;
+                parser.skip_whitespace();
+                if parser.eat("=") && !parser.eat("=") && parser.skip_whitespace() && parser.peek() == Some('{') {
+                    if let Some(table) = parser.table() {
+                        tables.push((ident, table));
+                        continue;
+                    }
+                }
+                parser.pos = checkpoint;
+            } else if !parser.skip_string() {
+                parser.advance();
+            }
+        }
+
+        tables
+    }
+
+    struct Parser<'a> {
+        src: &'a str,
+        pos: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        fn rest(&self) -> &'a str {
+            &self.src[self.pos..]
+        }
+
+        fn peek(&self) -> Option<char> {
+            self.rest().chars().next()
+        }
+
+        fn advance(&mut self) {
+            self.pos += self.peek().map(char::len_utf8).unwrap_or(0);
+        }
+
+        fn eat(&mut self, s: &str) -> bool {
+            let matches = self.rest().starts_with(s);
+            if matches {
+                self.pos += s.len();
+            }
+            matches
+        }
+
+        /// Skips whitespace and comments; always returns true, for chaining.
+        fn skip_whitespace(&mut self) -> bool {
+            loop {
+                let trimmed = self.rest().trim_start();
+                self.pos = self.src.len() - trimmed.len();
+
+                if self.eat("--") {
+                    if self.long_bracket().is_none() {
+                        let line_len = self.rest().find('\n').unwrap_or(self.rest().len());
+                        self.pos += line_len;
+                    }
+                } else {
+                    return true;
+                }
+            }
+        }
+
+        fn identifier(&mut self) -> Option<&'a str> {
+            let start = self.pos;
+            let len = self.rest()
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(self.rest().len());
+
+            let starts_with_digit = self.peek().is_some_and(|c| c.is_ascii_digit());
+            if len == 0 || starts_with_digit {
+                return None;
+            }
+
+            self.pos += len;
+            Some(&self.src[start..self.pos])
+        }
+
+        /// Skips a string literal, if one starts at the current position.
+        fn skip_string(&mut self) -> bool {
+            self.string().is_some()
+        }
+
+        fn string(&mut self) -> Option<String> {
+            match self.peek()? {
+                quote @ ('\'' | '"') => {
+                    self.advance();
+                    let mut s = String::new();
+                    loop {
+                        let c = self.peek()?;
+                        self.advance();
+                        match c {
+                            '\\' => {
+                                s.push(self.peek()?);
+                                self.advance();
+                            },
+                            c if c == quote => return Some(s),
+                            c => s.push(c),
+                        }
+                    }
+                },
+                '[' => self.long_bracket(),
+                _   => None,
+            }
+        }
+
+        /// `[[...]]` or `[==[...]==]`
+        fn long_bracket(&mut self) -> Option<String> {
+            let rest = self.rest();
+            let level = rest.strip_prefix('[')?.find(|c| c != '=')?;
+            if !rest[1 + level..].starts_with('[') {
+                return None;
+            }
+
+            let close = format!("]{}]", "=".repeat(level));
+            let content_start = level + 2;
+            let content_len = rest[content_start..].find(&close)?;
+            self.pos += content_start + content_len + close.len();
+            Some(rest[content_start..content_start + content_len].to_string())
+        }
+
+        fn table(&mut self) -> Option<LuaValue<'a>> {
+            if !self.eat("{") {
+                return None;
+            }
+
+            let mut entries = Vec::new();
+            loop {
+                self.skip_whitespace();
+                if self.eat("}") {
+                    return Some(LuaValue::Table(entries));
+                }
+
+                let entry = self.field()?;
+                entries.push(entry);
+
+                self.skip_whitespace();
+                if !self.eat(",") && !self.eat(";") && self.peek() != Some('}') {
+                    return None;
+                }
+            }
+        }
+
+        fn field(&mut self) -> Option<(Option<&'a str>, LuaValue<'a>)> {
+            let checkpoint = self.pos;
+
+            // `name = value`
+            if let Some(name) = self.identifier() {
+                self.skip_whitespace();
+                if self.rest().starts_with('=') && !self.rest().starts_with("==") {
+                    self.eat("=");
+                    self.skip_whitespace();
+                    return Some((Some(name), self.value()?));
+                }
+                self.pos = checkpoint;
+            }
+
+            // `["name"] = value`
+            if self.eat("[") {
+                self.skip_whitespace();
+                let key_start = self.pos;
+                if self.string().is_some() {
+                    let key = self.src[key_start + 1..self.pos - 1].trim();
+                    self.skip_whitespace();
+                    if self.eat("]") && self.skip_whitespace() && self.eat("=") {
+                        self.skip_whitespace();
+                        return Some((Some(key), self.value()?));
+                    }
+                }
+                self.pos = checkpoint;
+            }
+
+            Some((None, self.value()?))
+        }
+
+        fn value(&mut self) -> Option<LuaValue<'a>> {
+            let checkpoint = self.pos;
+            match self.peek()? {
+                '{' => return self.table(),
+                '\'' | '"' | '[' => if let Some(s) = self.string() {
+                    self.skip_whitespace();
+                    if matches!(self.peek(), Some(',' | ';' | '}')) {
+                        return Some(LuaValue::Str(s));
+                    }
+                    // e.g. string concatenation; keep as an expression
+                    self.pos = checkpoint;
+                },
+                _ => (),
+            }
+
+            self.expression()
+        }
+
+        /// Consumes source text up to the next `,`, `;` or `}` that isn't nested.
+        fn expression(&mut self) -> Option<LuaValue<'a>> {
+            let start = self.pos;
+            let mut depth = 0usize;
+
+            loop {
+                self.skip_whitespace();
+                match self.peek()? {
+                    ',' | ';' | '}' if depth == 0 => break,
+                    '\'' | '"'         => { self.string()?; },
+                    '[' if self.skip_string() => (),
+                    '(' | '{' | '['    => {
+                        depth += 1;
+                        self.advance();
+                    },
+                    ')' | '}' | ']'    => {
+                        depth = depth.checked_sub(1)?;
+                        self.advance();
+                    },
+                    _                  => self.advance(),
+                }
+            }
+
+            let expr = self.src[start..self.pos].trim();
+            (!expr.is_empty()).then_some(LuaValue::Expr(expr))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_assigned_tables() {
+            let src = r#"
+local config = wezterm.config_builder()
+-- config.keys = { commented out }
+config.keys = {
+  { key = 'a', mods = "CTRL", action = act.SplitHorizontal { domain = 'CurrentPaneDomain' } },
+  --[[ a block
+       comment ]]
+  { key = [[b]], action = wezterm.action.ActivateTab(-1) },
+}
+"#;
+
+            let tables = assigned_tables(src, &["keys"]);
+            assert_eq!(tables.len(), 1);
+
+            let (name, keys) = &tables[0];
+            assert_eq!(*name, "keys");
+
+            let bindings: Vec<_> = keys.entries().map(|(_, b)| b).collect();
+            assert_eq!(bindings.len(), 2);
+            assert_eq!(bindings[0].get("key"), Some(&LuaValue::Str("a".to_string())));
+            assert_eq!(bindings[0].get("action").map(LuaValue::text).as_deref(),
+                Some("act.SplitHorizontal { domain = 'CurrentPaneDomain' }"));
+            assert_eq!(bindings[1].get("key"), Some(&LuaValue::Str("b".to_string())));
+            assert_eq!(bindings[1].get("action").map(LuaValue::text).as_deref(),
+                Some("wezterm.action.ActivateTab(-1)"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_wezterm_lua() {
+        let input = r#"
+local wezterm = require 'wezterm'
+local act = wezterm.action
+
+return {
+  leader = { key = 'a', mods = 'CTRL', timeout_milliseconds = 1000 },
+  keys = {
+    { key = 'T', mods = 'CTRL|SHIFT', action = act.SpawnTab 'CurrentPaneDomain' },
+    { key = '|', mods = 'LEADER|SHIFT', action = act.SplitHorizontal { domain = 'CurrentPaneDomain' } },
+    { key = 'r', mods = 'LEADER', action = act.ActivateKeyTable { name = 'resize_pane', one_shot = false } },
+    { key = 'm', mods = 'CMD', action = wezterm.action.DisableDefaultAssignment },
+  },
+  key_tables = {
+    resize_pane = {
+      { key = 'LeftArrow', action = act.AdjustPaneSize { 'Left', 1 } },
+      { key = 'Escape', action = 'PopKeyTable' },
+    },
+  },
+}
+"#;
+
+        let keymap = parse_wezterm_lua(input);
+
+        assert_categories(&keymap, &[
+            ("WezTerm", 3),
+            ("resize_pane", 2),
+        ]);
+
+        let actions = keymap.actions_by_category("WezTerm");
+        assert_eq!(actions[0].name(), "SpawnTab 'CurrentPaneDomain'");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftShift), Char('t'),
+        ])]);
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('a')],
+            vec![Modifier(LeftShift), Char('|')],
+        ])]);
+
+        let resize = keymap.actions_by_category("resize_pane");
+        assert_eq!(resize[0].name(), "AdjustPaneSize { 'Left', 1 }");
+        assert_eq!(resize[0].shortcuts(), &[Shortcut::new(vec![Left])]);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::{key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+const CATEGORY: &str = "xbindkeys";
+
+/// Parses an `.xbindkeysrc` into a [KeyMap], labelling each key with the
+/// command it runs.
+///
+/// Keys given as raw keycodes (`m:0x4 + c:24`) or mouse buttons (`b:2`)
+/// can't be resolved and are left out.
+pub fn parse_xbindkeysrc(input: &str) -> KeyMap {
+    let mut bindings: Vec<(&str, Vec<Shortcut>)> = Vec::new();
+    let mut command: Option<&str> = None;
+
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(quoted) = line.strip_prefix('"') {
+            command = quoted.rfind('"').map(|end| &quoted[..end]);
+        } else if let Some(command) = command {
+            let Some(keystroke) = keystroke(line) else { continue };
+
+            let shortcut = Shortcut::new(keystroke);
+            match bindings.last_mut() {
+                // `xbindkeys -k` lists a key both by keycode and by keysym
+                Some((bound, shortcuts)) if *bound == command => shortcuts.push(shortcut),
+                _ => bindings.push((command, vec![shortcut])),
+            }
+        }
+    }
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    for (command, shortcuts) in bindings {
+        actions.entry(CATEGORY.to_string())
+            .or_default()
+            .push(Action::new_filter_empty(command, CATEGORY, shortcuts));
+    }
+
+    KeyMap::new("xbindkeys", actions).global()
+}
+
+pub struct XbindkeysFormat;
+
+impl KeymapFormat for XbindkeysFormat {
+    fn name(&self) -> &'static str {
+        "xbindkeys"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &[".xbindkeysrc", "xbindkeysrc"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        // a quoted command, followed by its indented key
+        input.lines()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|lines| {
+                let (command, key) = (lines[0].trim_end(), lines[1]);
+                command.len() > 1
+                    && command.starts_with('"')
+                    && command.ends_with('"')
+                    && key.starts_with(char::is_whitespace)
+                    && !key.trim().is_empty()
+            })
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_xbindkeysrc(input))
+    }
+}
+
+/// Parses a key, e.g. `control+shift + q` or `Mod4 + Return`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = key.split('+')
+        .map(str::trim)
+        // `Release` triggers on release, `Mod2` is numlock
+        .filter(|name| !matches!(name.to_ascii_lowercase().as_str(), "release" | "mod2"))
+        .collect();
+
+    let key = names.pop()?;
+    if key.contains(':') {
+        return None;
+    }
+
+    let mut keystroke = names.into_iter()
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.extend(key_names::unshifted(key_names::key_code(key)?));
+    Some(keystroke)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_xbindkeysrc() {
+        let input = r#"
+# specify a mouse button
+#"xterm"
+#  control + b:2
+
+"xterm"
+  control+shift + q
+
+"amixer set Master 5%+"
+    m:0x0 + c:123
+    XF86AudioRaiseVolume
+
+"xdotool key --clearmodifiers ctrl+c"
+    Release + Mod4 + c
+
+"firefox"
+    b:8
+"#;
+
+        let keymap = parse_xbindkeysrc(input);
+        assert_categories(&keymap, &[("xbindkeys", 3)]);
+
+        let actions = keymap.actions_by_category("xbindkeys");
+        assert_eq!(actions[0].name(), "xterm");
+        assert_eq!(actions[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftControl), Modifier(LeftShift), Char('q'),
+        ])]);
+        assert_eq!(actions[1].name(), "amixer set Master 5%+");
+        assert_eq!(actions[1].shortcuts(), &[Shortcut::new(vec![Media(crate::key_event::MediaKeyCode::RaiseVolume)])]);
+        assert_eq!(actions[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftMeta), Char('c')])]);
+    }
+}
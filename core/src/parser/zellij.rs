@@ -0,0 +1,135 @@
+use crate::key_event::KeyCode;
+use crate::keymap::KeyMap;
+use crate::parser::kdl::{parse_kdl, KdlNode};
+use crate::parser::{format::any_line_starts_with, key_names, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+
+/// Parses the `keybinds` block of a Zellij `config.kdl` into a [KeyMap], with
+/// one category per mode. Shared bindings (`shared_except "locked"`) are
+/// grouped under a category describing the modes they apply to.
+pub fn parse_zellij_config(input: &str) -> Result<KeyMap, String> {
+    let document = parse_kdl(input)
+        .ok_or("not valid KDL")?;
+
+    let modes = document.iter()
+        .filter(|node| node.name == "keybinds")
+        .flat_map(|keybinds| keybinds.children.iter());
+
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+    for mode in modes {
+        let category = mode_category(mode);
+        let bindings = mode.children.iter()
+            .filter(|node| node.name == "bind")
+            .filter_map(|bind| key_binding(&category, bind));
+
+        actions.entry(category.clone())
+            .or_default()
+            .extend(bindings);
+    }
+
+    actions.retain(|_, actions| !actions.is_empty());
+    Ok(KeyMap::new("Zellij", actions))
+}
+
+pub struct ZellijFormat;
+
+impl KeymapFormat for ZellijFormat {
+    fn name(&self) -> &'static str {
+        "zellij"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &["zellij/config.kdl", "zellij/*.kdl"]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        any_line_starts_with(input, "keybinds")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        parse_zellij_config(input)
+    }
+}
+
+fn mode_category(mode: &KdlNode) -> String {
+    match mode.name.as_str() {
+        "shared_except" => format!("all except {}", mode.args.join(", ")),
+        "shared_among"  => mode.args.join(", "),
+        "shared"        => "shared".to_string(),
+        name            => name.to_string(),
+    }
+}
+
+/// `bind "Alt h" "Alt Left" { MoveFocus "Left"; }`
+fn key_binding(category: &str, bind: &KdlNode) -> Option<Action> {
+    let label = bind.children.iter()
+        .map(KdlNode::describe)
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let shortcuts: Vec<Shortcut> = bind.args.iter()
+        .filter_map(|key| keystroke(key))
+        .map(Shortcut::new)
+        .collect();
+
+    (!label.is_empty() && !shortcuts.is_empty())
+        .then(|| Action::new_filter_empty(label, category.to_string(), shortcuts))
+}
+
+/// Parses a zellij key, e.g. `Ctrl g` or `Alt Shift Left`.
+fn keystroke(key: &str) -> Option<Vec<KeyCode>> {
+    let mut names: Vec<&str> = key.split_whitespace().collect();
+    let key = names.pop()?;
+
+    let mut keystroke = names.into_iter()
+        .map(|m| key_names::modifier(m).map(KeyCode::Modifier))
+        .collect::<Option<Vec<_>>>()?;
+
+    keystroke.push(key_names::key_code(key)?);
+    Some(keystroke)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+    use KeyCode::*;
+
+    #[test]
+    fn test_parse_zellij_config() {
+        let input = r#"
+keybinds clear-defaults=true {
+    locked {
+        bind "Ctrl g" { SwitchToMode "Normal"; }
+    }
+    pane {
+        bind "h" "Left" { MoveFocus "Left"; }
+        bind "n" { NewPane; SwitchToMode "Normal"; }
+    }
+    shared_except "locked" "pane" {
+        bind "Alt Shift Up" { Resize "Increase Up"; }
+    }
+}
+"#;
+
+        let keymap = parse_zellij_config(input).unwrap();
+
+        assert_categories(&keymap, &[
+            ("all except locked, pane", 1),
+            ("locked", 1),
+            ("pane", 2),
+        ]);
+
+        let pane = keymap.actions_by_category("pane");
+        assert_eq!(pane[0].name(), "MoveFocus Left");
+        assert_eq!(pane[0].shortcuts(), &[Shortcut::new(vec![Char('h')]), Shortcut::new(vec![Left])]);
+        assert_eq!(pane[1].name(), "NewPane; SwitchToMode Normal");
+
+        let shared = keymap.actions_by_category("all except locked, pane");
+        assert_eq!(shared[0].shortcuts(), &[Shortcut::new(vec![
+            Modifier(LeftAlt), Modifier(LeftShift), Up,
+        ])]);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::keymap::KeyMap;
+use crate::parser::{escape_sequence, format::any_line_starts_with, shell, KeymapFormat};
+use crate::shortcut::{Action, Shortcut};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+const DEFAULT_CATEGORY: &str = "main";
+
+/// Parses the output of zsh's `bindkey -L` (or `bindkey -LM` for all
+/// keymaps) into a [KeyMap], with one category per keymap.
+///
+/// Key sequences use zsh's notation, e.g. `^X^E` or `^[[1;5C`. Bindings of
+/// `self-insert` and `undefined-key` are left out, as are `-R` ranges.
+pub fn parse_zsh_bindkey(input: &str) -> KeyMap {
+    let mut actions: HashMap<String, Vec<Action>> = HashMap::new();
+
+    input.lines()
+        .filter_map(bindkey)
+        .for_each(|(category, action)| actions.entry(category).or_default().push(action));
+
+    KeyMap::new("zsh", actions)
+}
+
+pub struct ZshFormat;
+
+impl KeymapFormat for ZshFormat {
+    fn name(&self) -> &'static str {
+        "zsh"
+    }
+
+    fn file_globs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn sniff(&self, input: &str) -> bool {
+        any_line_starts_with(input, "bindkey ")
+    }
+
+    fn parse(&self, input: &str) -> Result<KeyMap, String> {
+        Ok(parse_zsh_bindkey(input))
+    }
+}
+
+/// `bindkey -M vicmd "^X^E" edit-command-line` or `bindkey -s "^Xa" "ls -l"`
+fn bindkey(line: &str) -> Option<(String, Action)> {
+    let mut words = words(line).into_iter();
+    if words.next()? != "bindkey" {
+        return None;
+    }
+
+    let mut category = DEFAULT_CATEGORY.to_string();
+    let mut send_string = false;
+
+    let key = loop {
+        let word = words.next()?;
+        match word.as_str() {
+            "-M" => category = words.next()?,
+            "-a" => category = "vicmd".to_string(),
+            "-s" => send_string = true,
+            "-R" => return None,
+            // -A, -N, -D, etc don't bind keys
+            w if w.starts_with('-') && w.len() > 1 => return None,
+            _    => break word,
+        }
+    };
+
+    let target = words.next()?;
+    let label = match target.as_str() {
+        _ if send_string                  => format!("send \"{target}\""),
+        "self-insert" | "undefined-key"   => return None,
+        widget                            => widget.to_string(),
+    };
+
+    let seq = escape_sequence::unescape(&key, true)?;
+    let shortcut = Shortcut::sequence(escape_sequence::keystrokes(&seq)?);
+
+    Some((category.clone(), Action::new_filter_empty(label, category, vec![shortcut])))
+}
+
+/// Quotes are removed from the words of a line, but backslash escapes are
+/// kept for [escape_sequence::unescape] to resolve.
+fn words(line: &str) -> Vec<String> {
+    let escape = |quote, chars: &mut Peekable<Chars>| match quote {
+        Some('\'') => Some("\\".to_string()),
+        _          => Some(std::iter::once('\\').chain(chars.next()).collect()),
+    };
+
+    shell::words(line, false, escape)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|w| w.text)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::parser::assert_categories;
+
+    #[test]
+    fn test_parse_zsh_bindkey() {
+        let input = r#"
+bindkey "^@" set-mark-command
+bindkey "^X^E" edit-command-line
+bindkey "^[[1;5C" forward-word
+bindkey "^[b" backward-word
+bindkey "^?" backward-delete-char
+bindkey "a" self-insert
+bindkey -R "\M-^@"-"\M-^?" self-insert
+bindkey -s "^Xl" "ls -l"
+bindkey -M vicmd "gg" beginning-of-buffer-or-history
+bindkey -a "^[OA" up-line-or-history
+"#;
+
+        let keymap = parse_zsh_bindkey(input);
+
+        assert_categories(&keymap, &[
+            ("main", 6),
+            ("vicmd", 2),
+        ]);
+
+        let main = keymap.actions_by_category("main");
+        assert_eq!(main[0].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Char(' ')])]);
+        assert_eq!(main[1].name(), "edit-command-line");
+        assert_eq!(main[1].shortcuts(), &[Shortcut::sequence(vec![
+            vec![Modifier(LeftControl), Char('x')],
+            vec![Modifier(LeftControl), Char('e')],
+        ])]);
+        assert_eq!(main[2].shortcuts(), &[Shortcut::new(vec![Modifier(LeftControl), Right])]);
+        assert_eq!(main[3].shortcuts(), &[Shortcut::new(vec![Modifier(LeftAlt), Char('b')])]);
+        assert_eq!(main[4].shortcuts(), &[Shortcut::new(vec![Backspace])]);
+        assert_eq!(main[5].name(), "send \"ls -l\"");
+
+        let vicmd = keymap.actions_by_category("vicmd");
+        assert_eq!(vicmd[0].shortcuts(), &[Shortcut::sequence(vec![vec![Char('g')], vec![Char('g')]])]);
+        assert_eq!(vicmd[1].shortcuts(), &[Shortcut::new(vec![Up])]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::ansi::{ansi, asciicast};
+use crate::dispatcher::Dispatcher;
+use crate::exabind_event::ExabindEvent;
+use crate::fx::overrides::EffectOverrides;
+use crate::fx::policy::EffectPolicy;
+use crate::headless::Headless;
+use crate::keymap::KeyMap;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Size;
+use tachyonfx::Duration;
+
+/// Formats the app can be recorded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// An asciinema v2 recording of all frames.
+    Asciicast,
+    /// The last frame as text with ANSI escape codes.
+    Ansi,
+}
+
+impl RecordFormat {
+    pub const ALL: &'static [RecordFormat] = &[
+        RecordFormat::Asciicast,
+        RecordFormat::Ansi,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecordFormat::Asciicast => "cast",
+            RecordFormat::Ansi      => "ansi",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|format| format.name() == name)
+    }
+}
+
+pub struct RecordOptions {
+    /// Size of the screen, in cells.
+    pub size: Size,
+    pub fps: u32,
+    /// Length of the recording.
+    pub duration: Duration,
+    /// Once a category is selected, selects the next one at this interval.
+    pub category_interval: Option<Duration>,
+    /// Effects to play, as in the interactive view.
+    pub effect_policy: EffectPolicy,
+    /// Effects replacing the built-in ones.
+    pub effect_overrides: EffectOverrides,
+}
+
+/// The frames of a recording.
+pub struct Recording {
+    pub frames: Vec<Buffer>,
+    /// The effect overrides that failed to compile while recording; see
+    /// [EffectOverrides::failures].
+    pub effect_failures: Vec<String>,
+}
+
+impl RecordOptions {
+    /// The time between frames; at least 1 ms, however high `fps` is.
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_millis((1000 / self.fps.max(1)).max(1))
+    }
+}
+
+/// Renders the frames of the app starting up with the keymap, one frame per
+/// `1 / fps` seconds of the effect timeline; see [Headless].
+pub fn record(keymap: KeyMap, options: &RecordOptions) -> Recording {
+    let frame_duration = options.frame_duration();
+    let frame_count = options.duration.as_millis() / frame_duration.as_millis() + 1;
+
+    let mut headless = Headless::with_effects(
+        keymap,
+        options.size,
+        frame_duration,
+        options.effect_policy,
+        options.effect_overrides.clone(),
+    );
+    let mut category_shown_for = 0;
+
+    let frames = (0..frame_count).map(|_| {
+        let has_selected_category = headless.app().keymap_context().category().is_some();
+        if let Some(interval) = options.category_interval.filter(|_| has_selected_category) {
+            category_shown_for += frame_duration.as_millis();
+            if category_shown_for >= interval.as_millis() {
+                headless.dispatch(ExabindEvent::NextCategory);
+                category_shown_for = 0;
+            }
+        }
+
+        headless.next_frame().clone()
+    }).collect();
+
+    let effect_failures = headless.app().effect_overrides().failures();
+    Recording { frames, effect_failures }
+}
+
+/// Records the app starting up with the keymap, in the given format, along
+/// with the [effect failures](Recording::effect_failures).
+pub fn record_as(keymap: KeyMap, options: &RecordOptions, format: RecordFormat) -> (String, Vec<String>) {
+    let Recording { frames, effect_failures } = record(keymap, options);
+    let recording = match format {
+        RecordFormat::Asciicast => asciicast(&frames, options.frame_duration()),
+        RecordFormat::Ansi      => frames.last().map(ansi).unwrap_or_default(),
+    };
+
+    (recording, effect_failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::kde_keymap;
+
+    fn options(fps: u32, duration: u32) -> RecordOptions {
+        RecordOptions {
+            size: Size::new(120, 30),
+            fps,
+            duration: Duration::from_millis(duration),
+            category_interval: None,
+            effect_policy: EffectPolicy::default(),
+            effect_overrides: EffectOverrides::default(),
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let options = options(10, 3000);
+
+        let recording = record(kde_keymap(), &options);
+        assert_eq!(recording.frames.len(), 31);
+        assert!(recording.frames.iter().all(|frame| frame.area.as_size() == options.size));
+        assert!(recording.effect_failures.is_empty());
+    }
+
+    #[test]
+    fn test_frame_duration_above_1000_fps() {
+        let options = options(2000, 10);
+
+        assert_eq!(options.frame_duration(), Duration::from_millis(1));
+        assert_eq!(record(kde_keymap(), &options).frames.len(), 11);
+    }
+
+    #[test]
+    fn test_record_with_effects() {
+        let recorded = |options: &RecordOptions| record(kde_keymap(), options).frames;
+        let default = recorded(&options(10, 1000));
+
+        let reduced_motion = RecordOptions {
+            effect_policy: EffectPolicy { reduced_motion: true, ..EffectPolicy::default() },
+            ..options(10, 1000)
+        };
+        assert_ne!(recorded(&reduced_motion), default);
+
+        let overrides = "startup = \"fx::dissolve(500)\"".parse().unwrap();
+        let overridden = RecordOptions {
+            effect_overrides: EffectOverrides::from_table(&overrides).unwrap(),
+            ..options(10, 1000)
+        };
+        assert_ne!(recorded(&overridden), default);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::key_event::ModifierKeyCode;
+use crate::keymap::KeyMap;
+use crate::shortcut::{Action, Shortcut};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Plain text listing of the actions, grouped by category. Only actions of
+/// `category` are listed, if given; if any `modifiers` are given, only
+/// shortcuts pressed with exactly those modifiers.
+pub fn list(keymap: &KeyMap, category: Option<&str>, modifiers: &[ModifierKeyCode]) -> String {
+    let bound = keymap.actions()
+        .filter(|action| category.is_none_or(|category| action.category() == category))
+        .flat_map(|action| action.shortcuts().iter().map(move |shortcut| (action, shortcut)))
+        .filter(|(_, shortcut)| modifiers.is_empty() || shortcut.uses_exactly_modifiers(modifiers));
+
+    by_category(bound)
+}
+
+/// Plain text listing of the actions bound to `shortcut`, including
+/// sequences starting with it.
+pub fn query(keymap: &KeyMap, shortcut: &Shortcut) -> String {
+    let bound = keymap.actions()
+        .flat_map(|action| action.shortcuts().iter().map(move |s| (action, s)))
+        .filter(|(_, s)| s.keystrokes().starts_with(shortcut.keystrokes()));
+
+    by_category(bound)
+}
+
+/// Plain text listing of all [conflicts](KeyMap::conflicts) and [shadowed
+/// shortcuts](KeyMap::shadowed_shortcuts).
+pub fn conflicts(keymap: &KeyMap) -> String {
+    let mut report = String::new();
+
+    for conflict in keymap.conflicts() {
+        let _ = writeln!(report, "{}", conflict.shortcut);
+        for action in &conflict.actions {
+            let _ = writeln!(report, "  {}", qualified_name(action));
+        }
+    }
+
+    for shadowed in keymap.shadowed_shortcuts() {
+        let _ = writeln!(report, "{} (shadowed)", shadowed.shortcut);
+        let _ = writeln!(report, "  {}", qualified_name(&shadowed.winner));
+        let _ = writeln!(report, "  {}", qualified_name(&shadowed.loser));
+    }
+
+    report
+}
+
+/// The name of the action prefixed by its category, e.g. "kitty: tabs:
+/// new_tab", but "kitty: scroll_line_up" rather than repeating the source
+/// when the category is named after it.
+fn qualified_name(action: &Action) -> String {
+    match action.own_category() == action.source() {
+        true  => format!("{}: {}", action.source(), action.name()),
+        false => format!("{}: {}", action.category(), action.name()),
+    }
+}
+
+fn by_category<'a>(bound: impl Iterator<Item=(&'a Action, &'a Shortcut)>) -> String {
+    let mut categories: BTreeMap<&str, Vec<(String, &str)>> = BTreeMap::new();
+    for (action, shortcut) in bound {
+        categories.entry(action.category())
+            .or_default()
+            .push((shortcut.to_string(), action.name()));
+    }
+
+    let mut report = String::new();
+    for (category, mut shortcuts) in categories {
+        shortcuts.sort();
+        let width = shortcuts.iter()
+            .map(|(shortcut, _)| shortcut.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let _ = writeln!(report, "{category}");
+        for (shortcut, name) in shortcuts {
+            let _ = writeln!(report, "  {shortcut:<width$}  {name}");
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::keymap::kde_keymap;
+    use std::collections::HashMap;
+
+    fn keymap() -> KeyMap {
+        let meta_shift = |key| Shortcut::new(vec![Modifier(LeftMeta), Modifier(LeftShift), key]);
+
+        kde_keymap().with_actions(vec![
+            Action::new_filter_empty("Window to Previous Screen", "kwin", vec![meta_shift(Left)]),
+        ])
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(list(&keymap(), Some("kwin"), &[]), [
+            "kwin",
+            "  Meta SHIFT ←  Window to Previous Screen",
+            "  Meta W        Overview",
+            "",
+        ].join("\n"));
+
+        assert_eq!(list(&keymap(), None, &[LeftMeta]), [
+            "kwin",
+            "  Meta W  Overview",
+            "plasmashell",
+            "  Meta Q  Activities",
+            "",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let ctrl = |c| Shortcut::new(vec![Modifier(LeftControl), Char(c)]);
+        let kitty = KeyMap::new("kitty", HashMap::from([
+            ("kitty".to_string(), vec![
+                Action::new_filter_empty("scroll_line_up", "kitty", vec![ctrl('k')]),
+                Action::new_filter_empty("clear_terminal", "kitty", vec![ctrl('k')]),
+            ]),
+            ("tabs".to_string(), vec![Action::new_filter_empty("close_tab", "tabs", vec![ctrl('q')])]),
+        ]));
+
+        let report = conflicts(&KeyMap::merge(vec![keymap(), kitty]));
+        let mut lines: Vec<&str> = report.lines().collect();
+        lines[1..].sort();
+        assert_eq!(lines, vec![
+            "CTRL K",
+            "  kitty: clear_terminal",
+            "  kitty: scroll_line_up",
+        ]);
+    }
+
+    #[test]
+    fn test_query() {
+        let shortcut = Shortcut::new(vec![Modifier(LeftShift), Modifier(LeftMeta), Left]);
+        assert_eq!(query(&keymap(), &shortcut), "kwin\n  Meta SHIFT ←  Window to Previous Screen\n");
+        assert_eq!(query(&keymap(), &Shortcut::new(vec![Char('x')])), "");
+    }
+}
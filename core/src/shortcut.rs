@@ -3,23 +3,69 @@ use crate::key_event::{KeyCode, ModifierKeyCode};
 use crate::crossterm::format_keycode;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Action {
     id: String,
+    /// Human-readable name, for formats where it differs from the id
+    label: Option<String>,
     category: String,
     shortcuts: Vec<Shortcut>,
+    /// Shortcuts the action is bound to out of the box, where known
+    default_shortcuts: Vec<Shortcut>,
+    source: String,
 }
 
+/// One or more keystrokes that trigger an action. Most shortcuts are a single
+/// keystroke; multi-stroke sequences (e.g. `C-x 4 f` in Emacs) keep each
+/// keystroke in the order it is pressed.
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shortcut {
-    keystroke: Vec<KeyCode>,
+    keystrokes: Vec<Vec<KeyCode>>,
 }
 
 impl Shortcut {
+    /// The first (or only) keystroke of the shortcut.
     pub fn keystroke(&self) -> &[KeyCode] {
-        &self.keystroke
+        &self.keystrokes[0]
+    }
+
+    /// All keystrokes, in the order they are pressed.
+    pub fn keystrokes(&self) -> &[Vec<KeyCode>] {
+        &self.keystrokes
+    }
+
+    /// All keys pressed over the course of the shortcut.
+    pub fn keys(&self) -> impl Iterator<Item=&KeyCode> {
+        self.keystrokes.iter().flatten()
+    }
+
+    pub fn is_sequence(&self) -> bool {
+        self.keystrokes.len() > 1
     }
 
     pub fn new(keystroke: Vec<KeyCode>) -> Self {
+        Self {
+            keystrokes: vec![Self::normalize(keystroke)],
+        }
+    }
+
+    /// Creates a multi-stroke shortcut; empty keystrokes are dropped.
+    pub fn sequence(keystrokes: Vec<Vec<KeyCode>>) -> Self {
+        let keystrokes: Vec<_> = keystrokes.into_iter()
+            .filter(|k| !k.is_empty())
+            .map(Self::normalize)
+            .collect();
+
+        if keystrokes.is_empty() {
+            Self::new(Vec::new())
+        } else {
+            Self { keystrokes }
+        }
+    }
+
+    /// orders modifiers before the remaining keys of a keystroke
+    fn normalize(keystroke: Vec<KeyCode>) -> Vec<KeyCode> {
         let (modifiers, keystroke): (Vec<KeyCode>, Vec<KeyCode>) = keystroke.into_iter()
             .partition(|k| matches!(k, KeyCode::Modifier(_)));
 
@@ -50,15 +96,21 @@ impl Shortcut {
             .filter(|m| modifiers.contains(m))
             .map(KeyCode::Modifier);
 
-        let keystroke = modifier_key_codes.chain(keystroke).collect();
+        modifier_key_codes.chain(keystroke).collect()
+    }
 
-        Self {
-            keystroke
-        }
+    /// Whether the first keystroke is pressed with exactly the given
+    /// modifiers, out of Control, Shift, Alt and Meta.
+    pub fn uses_exactly_modifiers(&self, modifiers: &[ModifierKeyCode]) -> bool {
+        use ModifierKeyCode::*;
+
+        [LeftControl, LeftShift, LeftAlt, LeftMeta].into_iter()
+            .all(|m| modifiers.contains(&m) == self.uses_modifier(m))
     }
 
+    /// Whether the first keystroke is pressed with the given modifier.
     pub fn uses_modifier(&self, key: ModifierKeyCode) -> bool {
-        self.keystroke.iter().any(|kc| {
+        self.keystroke().iter().any(|kc| {
             if let KeyCode::Modifier(m) = kc {
                 m == &key
             } else {
@@ -77,14 +129,49 @@ impl Action {
         Self {
             id: id.to_string(),
             category: category.to_string(),
-            shortcuts: shortcuts.into_iter().filter(|s| !s.keystroke.is_empty()).collect(),
+            shortcuts: shortcuts.into_iter().filter(|s| !s.keystroke().is_empty()).collect(),
+            label: None,
+            default_shortcuts: Vec::new(),
+            source: String::new(),
         }
     }
 
+    pub fn with_label<S: ToString>(mut self, label: S) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn with_default_shortcuts(mut self, shortcuts: Vec<Shortcut>) -> Self {
+        self.default_shortcuts = shortcuts.into_iter().filter(|s| !s.keystroke().is_empty()).collect();
+        self
+    }
+
+    /// The label of the action, falling back to its id.
     pub fn name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn id(&self) -> &str {
         &self.id
     }
 
+    pub fn default_shortcuts(&self) -> &[Shortcut] {
+        &self.default_shortcuts
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// The category of the action, without the source prefix added when
+    /// [merging](crate::KeyMap::merge) keymaps.
+    pub fn own_category(&self) -> &str {
+        self.category
+            .strip_prefix(self.source.as_str())
+            .and_then(|category| category.strip_prefix(": "))
+            .unwrap_or(&self.category)
+    }
+
     pub fn shortcuts(&self) -> &[Shortcut] {
         &self.shortcuts
     }
@@ -92,21 +179,35 @@ impl Action {
     pub fn update_category<S: ToString>(&mut self, category: S) {
         self.category = category.to_string();
     }
+
+    /// Name of the keymap the action was loaded from, e.g. "KDE" or "kitty".
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn update_source<S: ToString>(&mut self, source: S) {
+        self.source = source.to_string();
+    }
 }
 
 impl Display for Action {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let shortcuts = self.shortcuts.iter().map(Shortcut::to_string).collect::<Vec<_>>().join(", ");
-        write!(f, "{}: {}", self.id, shortcuts)
+        write!(f, "{}: {}", self.name(), shortcuts)
     }
 }
 
 impl Display for Shortcut {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let keystroke = self.keystroke.iter()
+        let format_keystroke = |keystroke: &Vec<KeyCode>| keystroke.iter()
             .map(|k| format_keycode(*k))
             .collect::<Vec<_>>()
             .join(" ");
-        write!(f, "{}", keystroke)
+
+        let keystrokes = self.keystrokes.iter()
+            .map(format_keystroke)
+            .collect::<Vec<_>>()
+            .join(" ▸ ");
+        write!(f, "{}", keystrokes)
     }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+//! Golden-file snapshots of rendered buffers, for tests.
+//!
+//! A snapshot is the text of the buffer, followed by a map of the cell styles
+//! where each distinct style is given a letter, and a legend of the letters.
+//! Snapshots are kept in `test/snapshots`; run the tests with
+//! `UPDATE_SNAPSHOTS=1` to write new or changed snapshots, and review the
+//! diff before committing them.
+
+use ratatui::buffer::{Buffer, CellDiffOption};
+use ratatui::style::{Color, Modifier};
+use std::path::PathBuf;
+
+/// Glyphs [draw_key_border](crate::widget::draw_key_border) falls back to
+/// for unexpected border combinations.
+pub const FALLBACK_GLYPHS: &[char] = &['X', 'Y', '#', '|'];
+
+/// Asserts that the buffer matches the snapshot `test/snapshots/{name}.txt`.
+pub fn assert_snapshot(name: &str, buf: &Buffer) {
+    let path = snapshot_path(name);
+    let actual = snapshot(buf);
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| panic!(
+        "no snapshot at {}; run with UPDATE_SNAPSHOTS=1 to create it", path.display()
+    ));
+
+    if expected != actual {
+        panic!("snapshot {name} differs; run with UPDATE_SNAPSHOTS=1 to update it\n{}",
+            line_diff(&expected, &actual));
+    }
+}
+
+/// The visible cells of the buffer holding any of the glyphs.
+pub fn find_glyphs(buf: &Buffer, glyphs: &[char]) -> Vec<(u16, u16, char)> {
+    let area = buf.area;
+    (area.top()..area.bottom())
+        .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+        .filter_map(|(x, y)| {
+            let cell = &buf[(x, y)];
+            let c = cell.symbol().chars().next()?;
+            (cell.diff_option != CellDiffOption::Skip && glyphs.contains(&c)).then_some((x, y, c))
+        })
+        .collect()
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../test/snapshots")
+        .join(format!("{name}.txt"))
+}
+
+/// The text of the buffer, its style map and the legend of the styles.
+fn snapshot(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut styles: Vec<(Color, Color, Modifier)> = Vec::new();
+
+    let mut text = String::new();
+    let mut style_map = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = &buf[(x, y)];
+            text.push_str(cell.symbol());
+
+            let style = (cell.fg, cell.bg, cell.modifier);
+            let idx = styles.iter().position(|s| *s == style).unwrap_or_else(|| {
+                styles.push(style);
+                styles.len() - 1
+            });
+            style_map.push(style_key(idx));
+        }
+        text.push('\n');
+        style_map.push('\n');
+    }
+
+    let legend: String = styles.iter().enumerate()
+        .map(|(idx, (fg, bg, modifier))| match modifier.is_empty() {
+            true  => format!("{}: fg={fg} bg={bg}\n", style_key(idx)),
+            false => format!("{}: fg={fg} bg={bg} {modifier:?}\n", style_key(idx)),
+        })
+        .collect();
+
+    format!("{text}\n{style_map}\n{legend}")
+}
+
+/// a-z, A-Z, 0-9 and then the Latin-1 letters from À on.
+fn style_key(idx: usize) -> char {
+    const KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    KEYS.chars()
+        .nth(idx)
+        .or_else(|| char::from_u32(0xc0 + (idx - KEYS.len()) as u32))
+        .unwrap()
+}
+
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    (0..expected.len().max(actual.len()))
+        .filter_map(|i| {
+            let (e, a) = (expected.get(i), actual.get(i));
+            (e != a).then(|| format!(
+                "line {}:\n  expected: {}\n  actual:   {}",
+                i + 1,
+                e.unwrap_or(&""),
+                a.unwrap_or(&""),
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
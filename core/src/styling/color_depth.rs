@@ -0,0 +1,253 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use std::path::PathBuf;
+use tachyonfx::color_to_hsl;
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB colours.
+    #[default]
+    TrueColor,
+    /// The 256-colour palette.
+    Ansi256,
+    /// The 16 ANSI colours.
+    Ansi16,
+}
+
+impl ColorDepth {
+    pub const ALL: &'static [ColorDepth] = &[
+        ColorDepth::TrueColor,
+        ColorDepth::Ansi256,
+        ColorDepth::Ansi16,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorDepth::TrueColor => "truecolor",
+            ColorDepth::Ansi256   => "256",
+            ColorDepth::Ansi16    => "16",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|depth| depth.name() == name)
+    }
+
+    /// Detects the colour depth from `COLORTERM`, or else from the `colors`
+    /// capability of `TERM`'s terminfo entry.
+    pub fn detect() -> ColorDepth {
+        let colorterm = std::env::var("COLORTERM").ok();
+        let term = std::env::var("TERM").ok();
+        let colors = term.as_deref().and_then(terminfo_colors);
+
+        Self::from_env(colorterm.as_deref(), term.as_deref(), colors)
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>, terminfo_colors: Option<u32>) -> ColorDepth {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+
+        match (term, terminfo_colors) {
+            // e.g. xterm-direct
+            (_, Some(colors)) if colors >= 1 << 24 => ColorDepth::TrueColor,
+            (_, Some(colors)) if colors >= 256     => ColorDepth::Ansi256,
+            (_, Some(_))                           => ColorDepth::Ansi16,
+            (Some(term), None) if term.contains("256color") => ColorDepth::Ansi256,
+            (Some(_), None)                        => ColorDepth::Ansi16,
+            // no TERM, e.g. the Windows console
+            (None, None)                           => ColorDepth::TrueColor,
+        }
+    }
+
+    /// The nearest colour the terminal can show.
+    pub fn quantize(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, color)               => color,
+            (_, Color::Reset)                            => Color::Reset,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b))   => Color::Indexed(ansi256(r, g, b)),
+            (ColorDepth::Ansi256, color)                 => color,
+            (ColorDepth::Ansi16, Color::Indexed(i)) if i < 16 => ANSI16[i as usize],
+            (ColorDepth::Ansi16, color @ (Color::Rgb(..) | Color::Indexed(_))) => ansi16(color),
+            (ColorDepth::Ansi16, color)                  => color,
+        }
+    }
+
+    /// Quantizes the colours of all cells.
+    pub fn quantize_buffer(&self, buf: &mut Buffer) {
+        if *self == ColorDepth::TrueColor {
+            return;
+        }
+
+        buf.content.iter_mut().for_each(|cell| {
+            cell.fg = self.quantize(cell.fg);
+            cell.bg = self.quantize(cell.bg);
+        });
+    }
+}
+
+/// The 16 ANSI colours, by index.
+const ANSI16: [Color; 16] = [
+    Color::Black, Color::Red, Color::Green, Color::Yellow,
+    Color::Blue, Color::Magenta, Color::Cyan, Color::Gray,
+    Color::DarkGray, Color::LightRed, Color::LightGreen, Color::LightYellow,
+    Color::LightBlue, Color::LightMagenta, Color::LightCyan, Color::White,
+];
+
+/// The nearest of the 6x6x6 colour cube or the grey ramp of the 256-colour palette.
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let nearest_level = |c: u8| (0..6)
+        .min_by_key(|&i| LEVELS[i].abs_diff(c))
+        .unwrap_or(0);
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+
+    let grey_idx = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(3) / 10;
+    let grey_idx = grey_idx.min(23) as u8;
+    let grey = 8 + grey_idx * 10;
+
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        [(r, cr), (g, cg), (b, cb)].iter()
+            .map(|(a, b)| (*a as i32 - *b as i32).pow(2))
+            .sum::<i32>()
+    };
+
+    if distance((grey, grey, grey)) < distance(cube) {
+        232 + grey_idx
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+/// The ANSI colour of the same hue, as the terminal's actual palette is
+/// unknown; greys and very light or dark colours map to the grey levels.
+fn ansi16(color: Color) -> Color {
+    let (h, s, l) = color_to_hsl(&color);
+
+    if s < 20.0 || !(12.0..=85.0).contains(&l) {
+        return match l {
+            l if l < 25.0 => Color::Black,
+            l if l < 50.0 => Color::DarkGray,
+            l if l < 80.0 => Color::Gray,
+            _             => Color::White,
+        };
+    }
+
+    const HUES: [usize; 6] = [1, 3, 2, 6, 4, 5]; // red, yellow, green, cyan, blue, magenta
+    let hue = HUES[((h + 30.0) / 60.0) as usize % 6];
+    ANSI16[if l >= 60.0 { hue + 8 } else { hue }]
+}
+
+/// The `colors` capability of the compiled terminfo entry of the terminal.
+fn terminfo_colors(term: &str) -> Option<u32> {
+    let first = term.chars().next()?;
+
+    terminfo_dirs().into_iter()
+        .flat_map(|dir| [
+            dir.join(first.to_string()).join(term),
+            // macOS names the directories by the hex code of the first letter
+            dir.join(format!("{:x}", first as u32)).join(term),
+        ])
+        .find_map(|path| std::fs::read(path).ok())
+        .and_then(|entry| parse_terminfo_colors(&entry))
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    dirs.extend(std::env::var_os("TERMINFO").map(PathBuf::from));
+    dirs.extend(dirs::home_dir().map(|home| home.join(".terminfo")));
+    if let Some(terminfo_dirs) = std::env::var_os("TERMINFO_DIRS") {
+        dirs.extend(std::env::split_paths(&terminfo_dirs));
+    }
+    dirs.extend(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo", "/usr/lib/terminfo"]
+        .map(PathBuf::from));
+
+    dirs
+}
+
+/// Reads the numeric `colors` capability from a compiled terminfo entry, see
+/// term(5).
+fn parse_terminfo_colors(entry: &[u8]) -> Option<u32> {
+    const COLORS: usize = 13;
+
+    let header = |i: usize| -> Option<usize> {
+        let bytes = entry.get(i * 2..i * 2 + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]).max(0) as usize)
+    };
+
+    // numbers are 16 bits in the legacy format, 32 bits in the extended one
+    let number_size = match header(0)? {
+        0o432  => 2,
+        0o1036 => 4,
+        _      => return None,
+    };
+
+    let (names_size, bools_count, numbers_count) = (header(1)?, header(2)?, header(3)?);
+    if numbers_count <= COLORS {
+        return None;
+    }
+
+    // numbers start on an even byte
+    let numbers = (12 + names_size + bools_count + 1) & !1;
+    let colors = entry.get(numbers + COLORS * number_size..numbers + (COLORS + 1) * number_size)?;
+
+    let colors = match number_size {
+        2 => i16::from_le_bytes([colors[0], colors[1]]) as i32,
+        _ => i32::from_le_bytes([colors[0], colors[1], colors[2], colors[3]]),
+    };
+
+    (colors > 0).then_some(colors as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ColorDepth::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(ColorDepth::from_env(Some("truecolor"), Some("xterm-256color"), Some(256)), TrueColor);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-256color"), Some(256)), Ansi256);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-direct"), Some(1 << 24)), TrueColor);
+        assert_eq!(ColorDepth::from_env(None, Some("linux"), Some(8)), Ansi16);
+        assert_eq!(ColorDepth::from_env(None, Some("screen-256color"), None), Ansi256);
+        assert_eq!(ColorDepth::from_env(None, Some("vt100"), None), Ansi16);
+    }
+
+    #[test]
+    fn test_quantize() {
+        let peach = Color::from_u32(0xfab387);
+        let crust = Color::from_u32(0x11111b);
+
+        assert_eq!(TrueColor.quantize(peach), peach);
+        assert_eq!(Ansi256.quantize(peach), Color::Indexed(216));
+        assert_eq!(Ansi256.quantize(Color::from_u32(0x808080)), Color::Indexed(244));
+        assert_eq!(Ansi256.quantize(Color::LightRed), Color::LightRed);
+
+        assert_eq!(Ansi16.quantize(peach), Color::LightRed);
+        assert_eq!(Ansi16.quantize(crust), Color::Black);
+        assert_eq!(Ansi16.quantize(Color::from_u32(0x1e66f5)), Color::Blue);
+        assert_eq!(Ansi16.quantize(Color::Indexed(12)), Color::LightBlue);
+        assert_eq!(Ansi16.quantize(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn test_parse_terminfo_colors() {
+        // header, names "xterm|x\0", 3 booleans, padding, numbers: 13 unset, then colors
+        let mut entry: Vec<u8> = [0o432_i16, 8, 3, 15, 0, 0].iter()
+            .flat_map(|n| n.to_le_bytes())
+            .collect();
+        entry.extend(b"xterm|x\0");
+        entry.extend([1, 0, 1, 0]);
+        entry.extend((0..13).flat_map(|_| (-1_i16).to_le_bytes()));
+        entry.extend(256_i16.to_le_bytes());
+        entry.extend(64_i16.to_le_bytes());
+
+        assert_eq!(parse_terminfo_colors(&entry), Some(256));
+        assert_eq!(parse_terminfo_colors(&entry[..40]), None);
+    }
+}
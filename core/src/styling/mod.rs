@@ -1,5 +1,5 @@
-mod catpuccin;
+mod color_depth;
 mod theme;
 
-pub use catpuccin::*;
-pub use theme::*;
\ No newline at end of file
+pub use color_depth::*;
+pub use theme::*;
@@ -1,93 +1,509 @@
 use ratatui::prelude::Modifier;
 use ratatui::style::{Color, Style};
-use tachyonfx::Interpolatable;
-use crate::styling::Catppuccin;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+use tachyonfx::{color_from_hsl, color_to_hsl, Interpolatable};
+use toml::{Table, Value};
+use crate::diff::ChangeKind;
+use crate::styling::ColorDepth;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Theme;
+/// Built-in themes, by name, as TOML theme files.
+pub const THEMES: &[(&str, &str)] = &[
+    ("catppuccin-mocha",     include_str!("themes/catppuccin-mocha.toml")),
+    ("catppuccin-macchiato", include_str!("themes/catppuccin-macchiato.toml")),
+    ("catppuccin-frappe",    include_str!("themes/catppuccin-frappe.toml")),
+    ("catppuccin-latte",     include_str!("themes/catppuccin-latte.toml")),
+    ("gruvbox",              include_str!("themes/gruvbox.toml")),
+    ("nord",                 include_str!("themes/nord.toml")),
+    ("solarized",            include_str!("themes/solarized.toml")),
+];
+
+/// Name of the [Theme::terminal] theme.
+const TERMINAL_THEME: &str = "terminal";
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The theme chosen with [select_theme], or Catppuccin Mocha.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Selects the theme of the UI for the rest of the run. Has no effect once
+/// anything has been rendered with the [theme].
+pub fn select_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The palette of the UI; see `themes/catppuccin-mocha.toml` for the format
+/// of theme files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Background of the screen and the keyboard.
+    pub background: Color,
+    /// Border of pressed keys.
+    pub base: Color,
+    /// Shortcuts hidden by the modifier filter.
+    pub muted: Color,
+    /// Key borders and labels, and the face of the function keys.
+    pub key_cap: Color,
+    pub active_modifier: Color,
+    pub active_modifier_bg: Color,
+    pub led: [Color; 3],
+    pub key_press: Color,
+    pub keystroke: Color,
+    pub label: Color,
+    pub added: Color,
+    pub removed: Color,
+    pub rebound: Color,
+    /// Base colours of the categories, repeating when there are more categories.
+    pub categories: Vec<Color>,
+    /// Whether the colours are the terminal's own palette, whose actual colours
+    /// are unknown; such colours are never blended.
+    pub terminal_palette: bool,
+    /// Colours the terminal can show; see [Theme::quantized].
+    pub color_depth: ColorDepth,
+}
+
+impl Theme {
+    /// Names of the built-in themes: those of [THEMES] and `terminal`.
+    pub fn names() -> Vec<&'static str> {
+        THEMES.iter()
+            .map(|(name, _)| *name)
+            .chain([TERMINAL_THEME])
+            .collect()
+    }
+
+    /// The built-in theme of the name, see [Theme::names].
+    pub fn named(name: &str) -> Option<Theme> {
+        if name == TERMINAL_THEME {
+            return Some(Theme::terminal());
+        }
+
+        THEMES.iter()
+            .find(|(theme, _)| *theme == name)
+            .map(|(_, toml)| Theme::from_toml(toml).expect("built-in theme to be valid"))
+    }
+
+    /// A built-in theme, or else a theme file at the path: a base16 scheme if
+    /// it ends with `.yaml` or `.yml`, otherwise a TOML theme.
+    pub fn load(name_or_path: &str) -> Result<Theme, String> {
+        if let Some(theme) = Theme::named(name_or_path) {
+            return Ok(theme);
+        }
+
+        let path = Path::new(name_or_path);
+        if !path.exists() {
+            return Err(format!(
+                "No theme named {name_or_path} and no theme file at that path\nBuilt-in themes: {}",
+                Theme::names().join(", ")
+            ));
+        }
+
+        let input = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+
+        let theme = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Theme::from_base16(&input),
+            _                    => Theme::from_toml(&input),
+        };
+
+        theme.map_err(|e| format!("Invalid theme {}: {e}", path.display()))
+    }
+
+    /// The terminal's own 16 colours, so that exabind follows the terminal's
+    /// colour scheme; categories are coloured by ANSI index.
+    pub fn terminal() -> Theme {
+        use Color::Indexed;
+
+        Theme {
+            background: Color::Reset,
+            base: Indexed(8),
+            muted: Indexed(8),
+            key_cap: Indexed(0),
+            active_modifier: Indexed(11),
+            active_modifier_bg: Indexed(8),
+            led: [Indexed(4), Indexed(2), Indexed(5)],
+            key_press: Indexed(6),
+            keystroke: Indexed(9),
+            label: Color::Reset,
+            added: Indexed(2),
+            removed: Indexed(1),
+            rebound: Indexed(3),
+            categories: [9, 11, 10, 14, 12, 13, 1, 3, 2, 6, 4, 5].map(Indexed).to_vec(),
+            terminal_palette: true,
+            color_depth: ColorDepth::TrueColor,
+        }
+    }
+
+    /// Parses a [base16](https://github.com/tinted-theming/home) scheme, either
+    /// with `baseXX: "rrggbb"` at the top level or under `palette:`.
+    pub fn from_base16(input: &str) -> Result<Theme, String> {
+        let palette: HashMap<String, Color> = input.lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter_map(|(key, value)| {
+                let key = key.trim().to_lowercase();
+                if key.len() != 6 || !key.starts_with("base") {
+                    return None;
+                }
+
+                // e.g. "282c34", "#282c34" or "282c34" # comment
+                let value = value.split_whitespace().next()?
+                    .trim_matches(['"', '\''])
+                    .trim_start_matches('#');
+
+                let rgb = u32::from_str_radix(value, 16).ok()?;
+                Some((key, Color::from_u32(rgb)))
+            })
+            .collect();
+
+        let base = |n: u8| palette.get(&format!("base{n:02x}"))
+            .copied()
+            .ok_or(format!("missing base{n:02X}"));
+
+        Ok(Theme {
+            background: base(0x00)?,
+            base: base(0x02)?,
+            muted: base(0x03)?,
+            key_cap: base(0x01)?,
+            active_modifier: base(0x09)?,
+            active_modifier_bg: base(0x02)?,
+            led: [base(0x0d)?, base(0x0b)?, base(0x0e)?],
+            key_press: base(0x0c)?,
+            keystroke: base(0x0a)?,
+            label: base(0x05)?,
+            added: base(0x0b)?,
+            removed: base(0x08)?,
+            rebound: base(0x0a)?,
+            categories: (0x08..=0x0f).map(base).collect::<Result<_, _>>()?,
+            terminal_palette: false,
+            color_depth: ColorDepth::TrueColor,
+        })
+    }
+
+    /// Parses a TOML theme file; all entries except `terminal_palette` are
+    /// required.
+    pub fn from_toml(input: &str) -> Result<Theme, String> {
+        let toml: Table = input.parse()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let [led_1, led_2, led_3] = colors(&toml, "led", "colors")?[..] else {
+            return Err("led.colors must have three colours".to_string());
+        };
+
+        let categories = colors(&toml, "categories", "colors")?;
+        if categories.is_empty() {
+            return Err("categories.colors must have at least one colour".to_string());
+        }
+
+        Ok(Theme {
+            background: color(&toml, "surface", "background")?,
+            base: color(&toml, "surface", "base")?,
+            muted: color(&toml, "surface", "muted")?,
+            key_cap: color(&toml, "key_cap", "color")?,
+            active_modifier: color(&toml, "key_cap", "active")?,
+            active_modifier_bg: color(&toml, "key_cap", "active_background")?,
+            led: [led_1, led_2, led_3],
+            key_press: color(&toml, "key_press", "color")?,
+            keystroke: color(&toml, "shortcuts", "keystroke")?,
+            label: color(&toml, "shortcuts", "label")?,
+            added: color(&toml, "changes", "added")?,
+            removed: color(&toml, "changes", "removed")?,
+            rebound: color(&toml, "changes", "rebound")?,
+            categories,
+            terminal_palette: toml.get("terminal_palette")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            color_depth: ColorDepth::TrueColor,
+        })
+    }
+}
+
+impl Theme {
+    /// The theme with its colours reduced to what the terminal can show; with
+    /// only 16 colours, they're no longer blended.
+    pub fn quantized(self, color_depth: ColorDepth) -> Theme {
+        let quantize = |c: Color| color_depth.quantize(c);
+
+        Theme {
+            background: quantize(self.background),
+            base: quantize(self.base),
+            muted: quantize(self.muted),
+            key_cap: quantize(self.key_cap),
+            active_modifier: quantize(self.active_modifier),
+            active_modifier_bg: quantize(self.active_modifier_bg),
+            led: self.led.map(quantize),
+            key_press: quantize(self.key_press),
+            keystroke: quantize(self.keystroke),
+            label: quantize(self.label),
+            added: quantize(self.added),
+            removed: quantize(self.removed),
+            rebound: quantize(self.rebound),
+            categories: self.categories.into_iter().map(quantize).collect(),
+            terminal_palette: self.terminal_palette || color_depth == ColorDepth::Ansi16,
+            color_depth,
+        }
+    }
+
+    /// Whether effects should keep to the theme's colours, rather than cycle
+    /// through shades derived from them.
+    pub fn simple_effects(&self) -> bool {
+        self.terminal_palette || self.color_depth != ColorDepth::TrueColor
+    }
+
+    /// Interpolates between the colours, or picks the nearer one of the two if
+    /// they're the terminal's.
+    fn blend(&self, from: Color, to: Color, alpha: f32) -> Color {
+        match (self.terminal_palette, alpha < 0.5) {
+            (false, _)    => from.lerp(&to, alpha),
+            (true, true)  => from,
+            (true, false) => to,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::named("catppuccin-mocha").expect("default theme to be built in")
+    }
+}
+
+fn entry<'a>(toml: &'a Table, table: &str, key: &str) -> Result<&'a Value, String> {
+    toml.get(table)
+        .and_then(Value::as_table)
+        .and_then(|t| t.get(key))
+        .ok_or(format!("missing {table}.{key}"))
+}
+
+fn color(toml: &Table, table: &str, key: &str) -> Result<Color, String> {
+    entry(toml, table, key)?
+        .as_str()
+        .and_then(|c| c.parse().ok())
+        .ok_or(format!("{table}.{key} is not a colour"))
+}
+
+fn colors(toml: &Table, table: &str, key: &str) -> Result<Vec<Color>, String> {
+    entry(toml, table, key)?
+        .as_array()
+        .and_then(|colors| colors.iter()
+            .map(|c| c.as_str()?.parse().ok())
+            .collect())
+        .ok_or(format!("{table}.{key} is not a list of colours"))
+}
 
 pub trait ExabindTheme {
+    fn background(&self) -> Color;
+
     fn kbd_surface(&self) -> Style;
     fn kbd_cap_border(&self) -> Style;
     fn kbd_cap_text(&self) -> Style;
+    fn kbd_cap_function_key(&self) -> Color;
     fn kbd_cap_outline_category(&self, category_index: usize) -> Style;
+    fn kbd_cap_outline(&self, base_color: Color) -> Style;
     fn kbd_led_colors(&self) -> [Color; 3];
 
     fn kbd_key_press_color(&self) -> Color;
+    fn kbd_key_press_border(&self) -> Style;
+    fn kbd_active_modifier(&self) -> Style;
 
     fn shortcuts_widget_keystroke(&self) -> Style;
     fn shortcuts_widget_label(&self) -> Style;
+    fn shortcuts_widget_disabled(&self) -> Style;
+    fn shortcuts_widget_bg(&self, base_color: Color) -> Color;
+    fn shortcuts_widget_border(&self, base_color: Color) -> Color;
+    fn shortcuts_widget_selected_row(&self, base_color: Color) -> Style;
     fn shortcuts_base_color(&self, category_index: usize) -> Color;
+
+    fn change_color(&self, change: ChangeKind) -> Color;
 }
 
 impl ExabindTheme for Theme {
+    fn background(&self) -> Color {
+        self.background
+    }
+
     fn kbd_surface(&self) -> Style {
         Style::default()
-            .bg(COLORS.crust)
+            .bg(self.background)
     }
 
     fn kbd_cap_border(&self) -> Style {
         Style::default()
-            .fg(COLORS.mantle)
-            .bg(COLORS.crust)
+            .fg(self.key_cap)
+            .bg(self.background)
     }
 
     fn kbd_cap_text(&self) -> Style {
         Style::default()
-            .fg(COLORS.mantle)
-            .bg(COLORS.crust)
+            .fg(self.key_cap)
+            .bg(self.background)
+    }
+
+    fn kbd_cap_function_key(&self) -> Color {
+        self.key_cap
     }
 
     fn kbd_cap_outline_category(&self, category_index: usize) -> Style {
-        let base_color = Self.shortcuts_base_color(category_index);
+        self.kbd_cap_outline(self.shortcuts_base_color(category_index))
+    }
+
+    fn kbd_cap_outline(&self, base_color: Color) -> Style {
         Style::default()
-            .fg(COLORS.crust.lerp(&base_color, 0.85))
+            .fg(self.blend(self.background, base_color, 0.85))
     }
 
     fn kbd_led_colors(&self) -> [Color; 3] {
-        [
-            COLORS.blue,
-            COLORS.green,
-            COLORS.mauve,
-        ]
+        self.led
     }
 
     fn kbd_key_press_color(&self) -> Color {
-        COLORS.sapphire
+        self.key_press
+    }
+
+    fn kbd_key_press_border(&self) -> Style {
+        Style::default()
+            .fg(self.base)
+    }
+
+    fn kbd_active_modifier(&self) -> Style {
+        Style::default()
+            .fg(self.active_modifier)
+            .bg(self.active_modifier_bg)
+            .add_modifier(Modifier::BOLD)
     }
 
     fn shortcuts_widget_keystroke(&self) -> Style {
         Style::default()
-            .fg(COLORS.flamingo)
+            .fg(self.keystroke)
             .add_modifier(Modifier::BOLD)
     }
 
     fn shortcuts_widget_label(&self) -> Style {
         Style::default()
-            .fg(COLORS.text)
+            .fg(self.label)
+    }
+
+    fn shortcuts_widget_disabled(&self) -> Style {
+        Style::default()
+            .fg(self.muted)
+    }
+
+    fn shortcuts_widget_bg(&self, base_color: Color) -> Color {
+        self.blend(self.background, base_color, 0.15)
+    }
+
+    fn shortcuts_widget_border(&self, base_color: Color) -> Color {
+        self.blend(self.background, base_color, 0.85)
+    }
+
+    fn shortcuts_widget_selected_row(&self, base_color: Color) -> Style {
+        let bg = if self.terminal_palette {
+            self.active_modifier_bg
+        } else {
+            let (h, s, l) = color_to_hsl(&base_color);
+            color_from_hsl(h, s, 0.0_f32.max(l - 15.0))
+        };
+
+        Style::default()
+            .bg(bg)
+            .add_modifier(Modifier::BOLD)
     }
 
     fn shortcuts_base_color(&self, category_index: usize) -> Color {
-        static BASE_COLORS: [Color; 14] = [
-            COLORS.rosewater,
-            COLORS.flamingo,
-            COLORS.pink,
-            COLORS.mauve,
-            COLORS.red,
-            COLORS.maroon,
-            COLORS.peach,
-            COLORS.yellow,
-            COLORS.green,
-            COLORS.teal,
-            COLORS.sky,
-            COLORS.sapphire,
-            COLORS.blue,
-            COLORS.lavender,
-        ];
-
-        BASE_COLORS[category_index % BASE_COLORS.len()]
+        self.categories[category_index % self.categories.len()]
+    }
+
+    fn change_color(&self, change: ChangeKind) -> Color {
+        match change {
+            ChangeKind::Added   => self.added,
+            ChangeKind::Removed => self.removed,
+            ChangeKind::Rebound => self.rebound,
+        }
     }
 }
 
-const COLORS: Catppuccin = Catppuccin::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_themes() {
+        for (name, toml) in THEMES {
+            assert!(Theme::from_toml(toml).is_ok(), "{name}");
+        }
+
+        let mocha = Theme::default();
+        assert_eq!(mocha.background, Color::from_u32(0x11111b));
+        assert_eq!(mocha.categories.len(), 14);
+    }
+
+    #[test]
+    fn test_theme_from_toml() {
+        let toml = THEMES[0].1.replace(r##"color = "#74c7ec""##, r#"color = "light-blue""#);
+        assert_eq!(Theme::from_toml(&toml).unwrap().key_press, Color::LightBlue);
 
+        let toml = THEMES[0].1.replace("[key_press]", "[key_pressed]");
+        assert_eq!(Theme::from_toml(&toml), Err("missing key_press.color".to_string()));
+
+        let toml = THEMES[0].1.replace(r##"colors = ["#89b4fa", "#a6e3a1", "#cba6f7"]"##, r##"colors = ["#89b4fa"]"##);
+        assert_eq!(Theme::from_toml(&toml), Err("led.colors must have three colours".to_string()));
+    }
+
+    #[test]
+    fn test_theme_from_base16() {
+        let scheme = r##"
+scheme: "Tomorrow Night"
+author: "Chris Kempson"
+base00: "1d1f21" # ----
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"##;
+        let theme = Theme::from_base16(scheme).unwrap();
+        assert_eq!(theme.background, Color::from_u32(0x1d1f21));
+        assert_eq!(theme.rebound, Color::from_u32(0xf0c674));
+        assert_eq!(theme.categories.len(), 8);
+
+        // the tinted-theming layout
+        let tinted = scheme.replace("base", "  base").replace(": \"", ": \"#");
+        let tinted = format!("system: \"base16\"\npalette:\n{tinted}");
+        assert_eq!(Theme::from_base16(&tinted), Ok(theme));
+
+        assert_eq!(
+            Theme::from_base16(&scheme.replace("base0F", "base0G")),
+            Err("missing base0F".to_string())
+        );
+    }
+
+    #[test]
+    fn test_terminal_theme_is_not_blended() {
+        let theme = Theme::terminal();
+        assert_eq!(theme.shortcuts_base_color(0), Color::Indexed(9));
+        assert_eq!(theme.kbd_cap_outline_category(0).fg, Some(Color::Indexed(9)));
+        assert_eq!(theme.shortcuts_widget_bg(Color::Indexed(9)), Color::Reset);
+    }
+
+    #[test]
+    fn test_quantized_theme() {
+        let theme = Theme::default().quantized(ColorDepth::Ansi256);
+        assert_eq!(theme.background, Color::Indexed(233));
+        assert!(!theme.terminal_palette);
+        assert!(theme.simple_effects());
+
+        let theme = Theme::default().quantized(ColorDepth::Ansi16);
+        assert_eq!(theme.background, Color::Black);
+        assert_eq!(theme.shortcuts_widget_bg(theme.categories[0]), Color::Black);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::keymap::KeyMap;
+use crate::shortcut::Action;
+use crate::styling::{theme, ExabindTheme};
+use crate::widget::{shortcut_key_caps, AnsiKeyboardTklLayout, KeyCap, KeyboardLayout};
+use ratatui::style::{Color, Modifier};
+use std::fmt::Write;
+use tachyonfx::ToRgbComponents;
+
+// key caps are laid out on the terminal's grid, with neighbours sharing
+// their borders; a grid cell is CELL_W x CELL_H pixels
+const CELL_W: u16 = 10;
+const CELL_H: u16 = 20;
+const GAP: u16 = 4;
+
+/// The keyboard as an SVG image, with the keys of `category`'s shortcuts
+/// outlined in the category's colour; without a category, the keys of every
+/// category are. When `modifiers` are given, only shortcuts pressed with
+/// exactly those modifiers count, and the modifier keys are shown pressed,
+/// like the TUI's modifier filter.
+pub fn keymap_svg(keymap: &KeyMap, category: Option<&str>, modifiers: &[ModifierKeyCode]) -> String {
+    let layout = AnsiKeyboardTklLayout;
+
+    let mut outlined: Vec<(KeyCap, Color)> = Vec::new();
+    for (category_idx, name) in keymap.category_names().into_iter().enumerate() {
+        if category.is_some_and(|category| category != name) {
+            continue;
+        }
+
+        let shortcuts = keymap.actions_by_category(name).iter()
+            .flat_map(Action::shortcuts)
+            .filter(|shortcut| modifiers.is_empty() || shortcut.uses_exactly_modifiers(modifiers));
+
+        // keys shared between categories keep the colour of the first one
+        let color = theme().shortcuts_base_color(category_idx);
+        for key_cap in shortcut_key_caps(&layout, shortcuts) {
+            if !outlined.iter().any(|(outlined, _)| outlined == &key_cap) {
+                outlined.push((key_cap, color));
+            }
+        }
+    }
+
+    let key_caps = layout.key_cap_lookup();
+    let pressed: Vec<KeyCap> = modifiers.iter()
+        .filter_map(|modifier| key_caps.get(&KeyCode::Modifier(*modifier)))
+        .cloned()
+        .collect();
+
+    keyboard_svg(&layout, &outlined, &pressed)
+}
+
+/// Renders the keyboard layout as an SVG image, each of the `outlined` key
+/// caps outlined in the given base colour and the `pressed` key caps filled
+/// in, as the TUI shows active modifiers.
+pub fn keyboard_svg(layout: &dyn KeyboardLayout, outlined: &[(KeyCap, Color)], pressed: &[KeyCap]) -> String {
+    let key_caps = layout.layout();
+    let width = key_caps.iter().map(|key_cap| key_cap.area.right()).max().unwrap_or(0) * CELL_W;
+    let height = key_caps.iter().map(|key_cap| key_cap.area.bottom()).max().unwrap_or(0) * CELL_H;
+
+    let mut svg = String::new();
+    let _ = writeln!(svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{width}" height="{height}" font-family="monospace" font-size="12">"#
+    );
+    let _ = writeln!(svg, r#"  <rect width="100%" height="100%" fill="{}"/>"#,
+        css_color(theme().kbd_surface().bg.unwrap_or(Color::Reset)));
+
+    for key_cap in &key_caps {
+        let outline = outlined.iter()
+            .find(|(outlined, _)| outlined == key_cap)
+            .map(|(_, color)| *color);
+
+        write_key_cap(&mut svg, key_cap, outline, pressed.contains(key_cap));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn write_key_cap(svg: &mut String, key_cap: &KeyCap, outline: Option<Color>, pressed: bool) {
+    let area = key_cap.area;
+
+    // the borders run through the middle of the key cap's first and last cells
+    let x = area.x * CELL_W + (CELL_W + GAP) / 2;
+    let y = area.y * CELL_H + (CELL_H + GAP) / 2;
+    let width = (area.width - 1) * CELL_W - GAP;
+    let height = (area.height - 1) * CELL_H - GAP;
+
+    let (stroke, stroke_width, mut label_color, mut label_opacity) = match outline {
+        Some(base_color) => {
+            let color = theme().kbd_cap_outline(base_color).fg.unwrap_or(base_color);
+            (color, 2, color, "1")
+        },
+        None => {
+            let border = theme().kbd_cap_border().fg.unwrap_or(Color::Reset);
+            let label = theme().shortcuts_widget_label().fg.unwrap_or(Color::Reset);
+            (border, 1, label, "0.6")
+        },
+    };
+
+    let mut fill = "none".to_string();
+    let mut font_weight = "normal";
+    if pressed {
+        let style = theme().kbd_active_modifier();
+        fill = css_color(style.bg.unwrap_or(Color::Reset));
+        label_color = style.fg.unwrap_or(label_color);
+        label_opacity = "1";
+        if style.add_modifier.contains(Modifier::BOLD) {
+            font_weight = "bold";
+        }
+    }
+
+    let _ = writeln!(svg,
+        r#"  <rect x="{x}" y="{y}" width="{width}" height="{height}" rx="4" fill="{fill}" stroke="{}" stroke-width="{stroke_width}"/>"#,
+        css_color(stroke),
+    );
+    let _ = writeln!(svg,
+        r#"  <text x="{}" y="{}" text-anchor="middle" dominant-baseline="central" fill="{}" fill-opacity="{label_opacity}" font-weight="{font_weight}">{}</text>"#,
+        x + width / 2,
+        y + height / 2,
+        css_color(label_color),
+        escape(&key_cap.label()),
+    );
+}
+
+/// The colour as a CSS colour value, e.g. `#f5e0dc`.
+pub(crate) fn css_color(color: Color) -> String {
+    match color {
+        Color::Reset => "currentColor".to_string(),
+        color        => {
+            let (r, g, b) = color.to_rgb();
+            format!("#{r:02x}{g:02x}{b:02x}")
+        },
+    }
+}
+
+/// Escapes text for use in XML and HTML.
+pub(crate) fn escape(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, c| {
+        match c {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c    => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::keymap::kde_keymap;
+    use crate::shortcut::Shortcut;
+
+    #[test]
+    fn test_keyboard_svg() {
+        let layout = AnsiKeyboardTklLayout;
+        let key_caps = layout.key_cap_lookup();
+        let outlined = [(key_caps[&Char('7')].clone(), Color::Rgb(0xf5, 0xe0, 0xdc))];
+
+        let svg = keyboard_svg(&layout, &[], &[]);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text ").count(), layout.layout().len());
+        assert!(svg.contains(">&#39;</text>"));
+
+        let svg = keyboard_svg(&layout, &outlined, &[]);
+        assert_eq!(svg.matches(r#"stroke-width="2""#).count(), 1);
+    }
+
+    #[test]
+    fn test_keymap_svg() {
+        let keymap = kde_keymap().with_actions(vec![
+            Action::new_filter_empty("Close Window", "kwin", vec![Shortcut::new(vec![Modifier(LeftAlt), F(4)])]),
+        ]);
+
+        let outlines = |svg: &str| svg.matches(r#"stroke-width="2""#).count();
+        let pressed = |svg: &str| svg.matches(r#"font-weight="bold""#).count();
+
+        let svg = keymap_svg(&keymap, None, &[]);
+        assert_eq!((outlines(&svg), pressed(&svg)), (5, 0));
+
+        let svg = keymap_svg(&keymap, Some("kwin"), &[]);
+        assert_eq!((outlines(&svg), pressed(&svg)), (4, 0));
+
+        let svg = keymap_svg(&keymap, Some("kwin"), &[LeftMeta]);
+        assert_eq!((outlines(&svg), pressed(&svg)), (2, 1));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"<a href="x">&</a>"#), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    }
+}
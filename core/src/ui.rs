@@ -0,0 +1,46 @@
+use crate::app::ExabindApp;
+use crate::stateful_widgets::StatefulWidgets;
+use crate::styling::{theme, ExabindTheme};
+use crate::ui_state::UiState;
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+use ratatui::Frame;
+use tachyonfx::Duration;
+
+/// Renders the keyboard and the shortcut widgets.
+pub fn ui(
+    f: &mut Frame<'_>,
+    stateful_widgets: &StatefulWidgets,
+    ui_state: &mut UiState
+) {
+    ui_state.screen = f.area().as_size();
+    if f.area().is_empty() || f.area().width == 2500 || f.area().height < 3 {
+        return;
+    }
+
+    Block::new()
+        .style(theme().kbd_surface())
+        .render(f.area(), f.buffer_mut());
+
+    ui_state.render_kbd(f.buffer_mut());
+
+    let area = f.area();
+
+    // shortcuts window
+    stateful_widgets.shortcuts
+        .iter()
+        .for_each(|w| w.render(area, f.buffer_mut(), &mut ui_state.shortcuts));
+}
+
+/// Processes the app's effects over the rendered frame, and reduces its
+/// colours to the theme's [color depth](crate::styling::ColorDepth).
+pub fn effects(
+    elapsed: Duration,
+    app: &mut ExabindApp,
+    f: &mut Frame<'_>,
+) {
+    let area = f.area();
+    app.process_effects(elapsed, f.buffer_mut(), area);
+
+    // effects blend colours the terminal may not have
+    theme().color_depth.quantize_buffer(f.buffer_mut());
+}
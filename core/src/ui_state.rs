@@ -1,8 +1,7 @@
-use crate::styling::{ExabindTheme, Theme, CATPPUCCIN};
+use crate::styling::{theme, ExabindTheme};
 use crate::widget::{KeyCap, KeyboardLayout, KeyboardWidget, ShortcutsWidgetState};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Offset, Rect, Size};
-use ratatui::style::{Modifier, Style};
 use ratatui::widgets::{Block, Widget};
 use tachyonfx::{ref_count, BufferRenderer, Duration, Effect, EffectManager, RefCount};
 use crate::fx::effect::UniqueEffectId;
@@ -72,7 +71,7 @@ impl UiState {
 
         let area = buf.area;
         Block::default()
-            .style(Theme.kbd_surface())
+            .style(theme().kbd_surface())
             .render(area, &mut buf);
 
         let kbd = KeyboardWidget::new(kbd.layout());
@@ -100,7 +99,7 @@ impl UiState {
         // render active modifiers
         (&KeyboardWidget::new_with_style(
             self.kbd.active_modifiers.clone(),
-            Style::default().fg(CATPPUCCIN.peach).bg(CATPPUCCIN.surface0).add_modifier(Modifier::BOLD),
+            theme().kbd_active_modifier(),
             None,
         )).render(area, &mut work_buf);
     }
@@ -133,3 +132,27 @@ impl UiState {
             .render_buffer(Offset::default(), &mut buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_event::KeyCode;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::snapshot::assert_snapshot;
+    use crate::widget::AnsiKeyboardTklLayout;
+
+    #[test]
+    fn test_render_kbd() {
+        let mut ui_state = UiState::new();
+        ui_state.reset_kbd_buffer(AnsiKeyboardTklLayout);
+        ui_state.update_active_modifiers(vec![
+            AnsiKeyboardTklLayout.key_cap_lookup()[&KeyCode::Modifier(LeftMeta)].clone(),
+        ]);
+        ui_state.apply_kbd_effects(Duration::from_millis(0));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 95, 14));
+        ui_state.render_kbd(&mut buf);
+
+        assert_snapshot("render_kbd", &buf);
+    }
+}
@@ -1,5 +1,6 @@
-use crate::styling::{Catppuccin, ExabindTheme, Theme};
+use crate::styling::{theme, ExabindTheme};
 use crate::key_event::{KeyCode, ModifierKeyCode};
+use crate::shortcut::Shortcut;
 use ratatui::buffer::{Buffer, Cell};
 use ratatui::layout::{Alignment, Margin, Rect, Size};
 use ratatui::prelude::Position;
@@ -190,6 +191,25 @@ impl KeyboardLayout for AnsiKeyboardTklLayout {
     }
 }
 
+/// The key caps pressed for any of the shortcuts, in reading order. Shifted
+/// characters resolve to their unshifted key, e.g. `?` to `/`.
+pub fn shortcut_key_caps<'a>(
+    layout: &dyn KeyboardLayout,
+    shortcuts: impl IntoIterator<Item = &'a Shortcut>,
+) -> Vec<KeyCap> {
+    let key_caps = layout.key_cap_lookup();
+
+    let mut pressed: Vec<KeyCap> = shortcuts.into_iter()
+        .flat_map(Shortcut::keys)
+        .filter_map(|key_code| key_caps.get(&supplant_key_code(*key_code)))
+        .cloned()
+        .collect();
+
+    pressed.sort_by_key(|key_cap| (key_cap.area.y, key_cap.area.x));
+    pressed.dedup();
+    pressed
+}
+
 pub fn render_border_with<F>(
     key_caps: &[KeyCap],
     buf: &mut Buffer,
@@ -255,8 +275,8 @@ impl KeyboardWidget {
     ) -> Self {
         Self::new_with_style(
             keys,
-            Theme.kbd_cap_text(),
-            Some(Theme.kbd_cap_border()),
+            theme().kbd_cap_text(),
+            Some(theme().kbd_cap_border()),
         )
     }
 
@@ -305,10 +325,8 @@ impl KeyCapWidget {
         border_style: Option<Style>,
     ) -> Self {
 
-        let colors = Catppuccin::new();
-
         use KeyCode::*;
-        let other_color = colors.mantle;
+        let other_color = theme().kbd_cap_function_key();
         let cap_style = match key_cap.key_code {
             Esc
             | Tab
@@ -347,7 +365,32 @@ impl KeyCapWidget {
     }
 
     pub fn render_keypad(&self, buf: &mut Buffer) {
-        let key_string = match self.key_cap.key_code {
+        let key_string = self.key_cap.label();
+
+        let alignment = match key_string.char_indices().count() {
+            1 => Alignment::Center,
+            _ => Alignment::Left,
+        };
+
+
+        Text::from(Span::from(key_string))
+            .style(self.cap_style)
+            .alignment(alignment)
+            .render(self.key_cap.area.inner(Margin::new(1, 1)), buf);
+    }
+}
+
+impl KeyCap {
+    pub fn new(key_code: KeyCode, area: Rect) -> Self {
+        Self {
+            key_code,
+            area,
+        }
+    }
+
+    /// The text printed on the key cap.
+    pub fn label(&self) -> String {
+        match self.key_code {
             KeyCode::Esc => "ESC".to_string(),
             KeyCode::F(n) => format!("F{}", n),
             KeyCode::Char(' ') => "␣".to_string(),
@@ -390,26 +433,6 @@ impl KeyCapWidget {
             KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift) => "Iso3".to_string(),
             KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift) => "Iso5".to_string(),
             KeyCode::Unidentified => "???".to_string(),
-        };
-
-        let alignment = match key_string.char_indices().count() {
-            1 => Alignment::Center,
-            _ => Alignment::Left,
-        };
-
-
-        Text::from(Span::from(key_string))
-            .style(self.cap_style)
-            .alignment(alignment)
-            .render(self.key_cap.area.inner(Margin::new(1, 1)), buf);
-    }
-}
-
-impl KeyCap {
-    pub fn new(key_code: KeyCode, area: Rect) -> Self {
-        Self {
-            key_code,
-            area,
         }
     }
 }
@@ -542,3 +565,55 @@ const SPACE_W: u16 = 31;
 const SUPER_W: u16 = 6;
 const MENU_W: u16 = 6;
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{assert_snapshot, find_glyphs, FALLBACK_GLYPHS};
+
+    fn keyboard_area() -> Rect {
+        Rect::new(0, 0, 95, 14)
+    }
+
+    #[test]
+    fn test_keyboard_widget() {
+        let mut buf = Buffer::empty(keyboard_area());
+        (&KeyboardWidget::new(AnsiKeyboardTklLayout.layout())).render(buf.area, &mut buf);
+
+        assert_snapshot("keyboard_widget", &buf);
+    }
+
+    #[test]
+    fn test_key_borders_have_no_fallback_glyphs() {
+        let mut buf = Buffer::empty(keyboard_area());
+        render_border_with(&AnsiKeyboardTklLayout.layout(), &mut buf, |decorate, _pos, cell| {
+            draw_key_border(decorate, cell);
+        });
+
+        assert_eq!(find_glyphs(&buf, FALLBACK_GLYPHS), vec![]);
+    }
+
+    #[test]
+    fn test_adjacent_key_borders_have_no_fallback_glyphs() {
+        // outlines are drawn top to bottom, as ordered by shortcut_key_caps
+        let mut layout = AnsiKeyboardTklLayout.layout();
+        layout.sort_by_key(|key_cap| (key_cap.area.y, key_cap.area.x));
+
+        let mut fallbacks = Vec::new();
+        for (i, a) in layout.iter().enumerate() {
+            for b in &layout[i + 1..] {
+                let mut buf = Buffer::empty(keyboard_area());
+                render_border_with(&[a.clone(), b.clone()], &mut buf, |decorate, _pos, cell| {
+                    draw_key_border(decorate, cell);
+                });
+
+                let glyphs = find_glyphs(&buf, FALLBACK_GLYPHS);
+                if !glyphs.is_empty() {
+                    fallbacks.push((a.key_code, b.key_code, glyphs));
+                }
+            }
+        }
+
+        assert_eq!(fallbacks, vec![]);
+    }
+}
@@ -5,7 +5,7 @@ mod shortcut_categories;
 pub use keyboard::*;
 pub use shortcuts::*;
 use crate::app::KeyMapContext;
-use crate::styling::{ExabindTheme, Theme};
+use crate::styling::{theme, ExabindTheme};
 
 pub fn shortcut_widgets(context: &KeyMapContext) -> Vec<ShortcutsWidget> {
     context.unordered_categories().iter()
@@ -15,12 +15,12 @@ pub fn shortcut_widgets(context: &KeyMapContext) -> Vec<ShortcutsWidget> {
 
 fn shortcut_widget(context: &KeyMapContext, category: &str) -> ShortcutsWidget {
     let (category_idx, actions) = context.filtered_actions_by_category(category);
-    let base_color = Theme.shortcuts_base_color(category_idx);
+    let base_color = context.category_base_color(category_idx);
 
     ShortcutsWidget::new(
         category.to_string(),
-        Theme.shortcuts_widget_keystroke(),
-        Theme.shortcuts_widget_label(),
+        theme().shortcuts_widget_keystroke(),
+        theme().shortcuts_widget_label(),
         base_color,
         actions
     )
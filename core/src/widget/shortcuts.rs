@@ -1,12 +1,11 @@
 use crate::app::BoundShortcut;
-use crate::styling::{Catppuccin, CATPPUCCIN};
+use crate::styling::{theme, ExabindTheme};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Position, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols::border::Set;
 use ratatui::text::{Span, Text};
 use ratatui::widgets::{Block, Clear, Row, StatefulWidget, Table, TableState, Widget};
-use tachyonfx::{color_from_hsl, color_to_hsl, Interpolatable};
 
 pub struct ShortcutsWidget {
     shortcuts: Vec<BoundShortcut>,
@@ -42,19 +41,15 @@ impl ShortcutsWidget {
             .max()
             .unwrap_or(0);
 
-        let (h, s, l) = color_to_hsl(&base_color);
-
-        let selected_row_style = Style::default()
-            .bg(color_from_hsl(h, s, 0.0_f32.max(l - 15.0)))
-            .add_modifier(Modifier::BOLD);
+        let selected_row_style = theme().shortcuts_widget_selected_row(base_color);
 
         let constraints = [
             Constraint::Length(width_shortcut as _),
             Constraint::Length(width_name as _),
         ];
 
-        let bg_color = CATPPUCCIN.crust.lerp(&base_color, 0.15);
-        let border_color = CATPPUCCIN.crust.lerp(&base_color, 0.85);
+        let bg_color = theme().shortcuts_widget_bg(base_color);
+        let border_color = theme().shortcuts_widget_border(base_color);
         let border_style = Style::default().fg(border_color);
 
         let mut title2 = title.clone();
@@ -120,10 +115,10 @@ fn shortcut_as_table_row<'a>(
         Row::new([shortcuts, name])
     } else {
         let name = Text::from(bound_shortcut.name().to_string())
-            .style(Style::default().fg(Catppuccin::new().surface2));
+            .style(theme().shortcuts_widget_disabled());
 
         let shortcuts = Text::from(shortcut.to_string())
-            .style(Style::default().fg(Catppuccin::new().surface2));
+            .style(theme().shortcuts_widget_disabled());
 
         Row::new([shortcuts, name])
     }
@@ -160,14 +155,14 @@ impl StatefulWidget for &ShortcutsWidget {
         for xy in border_south.positions() {
             if let Some(c) = buf.cell_mut(xy) {
                 let style = c.style();
-                c.set_style(style.bg(CATPPUCCIN.crust));
+                c.set_style(style.bg(theme().background()));
             };
         }
 
         let top_left = translated_area.as_position();
         if let Some(c) = buf.cell_mut(top_left) {
             let style = c.style();
-            c.set_style(style.bg(CATPPUCCIN.crust));
+            c.set_style(style.bg(theme().background()));
         }
     }
 }
@@ -181,4 +176,36 @@ const SHORTCUT_SET_2: Set = Set {
     vertical_right:    "▕",
     horizontal_top:    "▔",
     horizontal_bottom: "▔",
-};
\ No newline at end of file
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ExabindApp;
+    use crate::key_event::KeyCode::*;
+    use crate::key_event::ModifierKeyCode::*;
+    use crate::keymap::kde_keymap;
+    use crate::shortcut::{Action, Shortcut};
+    use crate::snapshot::assert_snapshot;
+    use crate::ui_state::UiState;
+    use ratatui::layout::Size;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_shortcuts_widget() {
+        let keymap = kde_keymap().with_actions(vec![
+            Action::new_filter_empty("Window Close", "kwin", vec![Shortcut::new(vec![Modifier(LeftAlt), F(4)])]),
+            Action::new_filter_empty("Switch to Desktop 1", "kwin", vec![Shortcut::new(vec![Modifier(LeftControl), F(1)])]),
+        ]);
+
+        let mut ui_state = UiState::new();
+        ui_state.screen = Size::new(120, 30);
+        let app = ExabindApp::new(&mut ui_state, mpsc::channel().0, keymap);
+
+        let widget = &app.stateful_widgets().category_widgets()[0];
+        let mut buf = Buffer::empty(widget.area());
+        widget.render(buf.area, &mut buf, &mut ui_state.shortcuts);
+
+        assert_snapshot("shortcuts_widget", &buf);
+    }
+}
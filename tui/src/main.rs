@@ -1,43 +1,145 @@
 use exabind_core::{
-    parse_args, IntoKeyMap, parser::kde::parse_kglobalshortcuts,
+    parse_args, KeyMap,
     app::ExabindApp,
+    args::{Cli, Invocation, KeymapSource},
+    diff::KeymapDiff,
+    export,
+    fx::overrides::EffectOverrides,
+    fx::policy::EffectPolicy,
     event_handler::EventHandler,
-    fx::effect::{open_all_categories, starting_up},
-    stateful_widgets::StatefulWidgets,
-    styling::CATPPUCCIN,
+    report,
+    recording,
+    styling::{self, ColorDepth},
+    svg,
     tui::Tui,
     widget::AnsiKeyboardTklLayout,
+    ui::{effects, ui},
     ui_state,
 };
 use ::crossterm::event::{KeyboardEnhancementFlags, PushKeyboardEnhancementFlags};
 use ::crossterm::execute;
-use ratatui::prelude::Frame;
-use ratatui::style::Style;
-use ratatui::widgets::{Block, StatefulWidget};
+use ratatui::style::Color;
+use std::collections::HashMap;
 use std::io;
 use std::io::stdout;
-use tachyonfx::Duration;
 
 fn main() -> io::Result<()> {
-    let shortcuts_path = match parse_args() {
-        Ok(path) => path,
+    let Cli { invocation, theme, color_depth, effect_policy, effect_overrides } = match parse_args() {
+        Ok(cli) => cli,
         Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     };
 
+    // only the interactive views follow the terminal, other output is in truecolor unless asked
+    let color_depth = color_depth.unwrap_or_else(|| match invocation {
+        Invocation::View(_) | Invocation::Diff { tui: true, .. } => ColorDepth::detect(),
+        _ => ColorDepth::TrueColor,
+    });
+    styling::select_theme(theme.quantized(color_depth));
+
+    match invocation {
+        Invocation::View(keymap_sources) => {
+            // let keymap = PathBuf::from("test/Eclipse copy.xml").parse_jetbrains_keymap();
+            // let keymap = PathBuf::from("test/default.xml").parse_jetbrains_keymap();
+            let keymap = merged(&keymap_sources)
+                .with_shadowed_category();
+
+            run(keymap, HashMap::new(), effect_policy, effect_overrides)
+        },
+        Invocation::Diff { old, new, tui } => {
+            let diff = KeymapDiff::new(&old.keymap(), &new.keymap());
+            if diff.is_empty() {
+                println!("No changes");
+                Ok(())
+            } else if tui {
+                run(diff.to_keymap(), diff.category_colors(), effect_policy, effect_overrides)
+            } else {
+                print!("{diff}");
+                Ok(())
+            }
+        },
+        Invocation::List { sources, category, modifiers } => {
+            print!("{}", report::list(&merged(&sources), category.as_deref(), &modifiers));
+            Ok(())
+        },
+        Invocation::Query { sources, shortcut } => {
+            let bound = report::query(&merged(&sources), &shortcut);
+            if bound.is_empty() {
+                println!("Nothing is bound to {shortcut}");
+                std::process::exit(1);
+            }
+
+            print!("{bound}");
+            Ok(())
+        },
+        Invocation::Conflicts(sources) => {
+            let conflicts = report::conflicts(&merged(&sources));
+            if !conflicts.is_empty() {
+                print!("{conflicts}");
+                std::process::exit(1);
+            }
+
+            Ok(())
+        },
+        Invocation::Svg { sources, category, modifiers, output } => {
+            let svg = svg::keymap_svg(&merged(&sources), category.as_deref(), &modifiers);
+            match output {
+                Some(path) => std::fs::write(path, svg)?,
+                None => print!("{svg}"),
+            }
+
+            Ok(())
+        },
+        Invocation::Record { sources, options, format, output } => {
+            let (recording, effect_failures) = recording::record_as(merged(&sources), &options, format);
+            match output {
+                Some(path) => std::fs::write(path, recording)?,
+                None => print!("{recording}"),
+            }
+
+            for failure in effect_failures {
+                eprintln!("Warning: {failure}; the built-in effect was used instead");
+            }
+
+            Ok(())
+        },
+        Invocation::Export { sources, format } => {
+            match export::export(&merged(&sources), format) {
+                Ok(exported) => print!("{exported}"),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                },
+            }
+
+            Ok(())
+        },
+    }
+}
+
+fn merged(sources: &[KeymapSource]) -> KeyMap {
+    KeyMap::merge(sources.iter()
+        .map(KeymapSource::keymap)
+        .collect())
+}
+
+fn run(
+    keymap: KeyMap,
+    category_colors: HashMap<String, Color>,
+    effect_policy: EffectPolicy,
+    effect_overrides: EffectOverrides,
+) -> io::Result<()> {
     let events = EventHandler::new(std::time::Duration::from_millis(33));
-    // let keymap = PathBuf::from("test/Eclipse copy.xml").parse_jetbrains_keymap();
-    // let keymap = PathBuf::from("test/default.xml").parse_jetbrains_keymap();
-    let keymap = shortcuts_path
-        .into_keymap(parse_kglobalshortcuts);
 
     let mut ui_state = ui_state::UiState::new();
     let sender = events.sender();
     let mut tui = Tui::new(ratatui::init(), events);
     ui_state.screen = tui.size();
-    let mut app = ExabindApp::new(&mut ui_state, sender, keymap);
+    let mut app = ExabindApp::with_category_colors(&mut ui_state, sender, keymap, category_colors)
+        .with_effect_policy(effect_policy)
+        .with_effect_overrides(effect_overrides);
 
     execute!(
         stdout(),
@@ -48,11 +150,7 @@ fn main() -> io::Result<()> {
     )?;
 
     ui_state.reset_kbd_buffer(AnsiKeyboardTklLayout);
-    ui_state.register_kbd_effect(starting_up());
-
-    let widgets = app.stateful_widgets().category_widgets();
-    let open_categories_fx = open_all_categories(app.sender(), widgets);
-    app.stage_mut().add_effect(open_categories_fx);
+    app.start_up(&mut ui_state);
 
     while app.is_running() {
         let elapsed = app.update_time();
@@ -67,41 +165,10 @@ fn main() -> io::Result<()> {
         })?;
     }
     ratatui::restore();
-    Ok(())
-}
 
-fn effects(
-    elapsed: Duration,
-    app: &mut ExabindApp,
-    f: &mut Frame<'_>,
-) {
-    let area = f.area();
-    app.process_effects(elapsed, f.buffer_mut(), area);
-}
-
-fn ui(
-    f: &mut Frame<'_>,
-    stateful_widgets: &StatefulWidgets,
-    ui_state: &mut ui_state::UiState
-) {
-    use ratatui::prelude::Widget;
-
-    ui_state.screen = f.area().as_size();
-    if f.area().is_empty() || f.area().width == 2500 || f.area().height < 3 {
-        return;
+    for failure in app.effect_overrides().failures() {
+        eprintln!("Warning: {failure}; the built-in effect was used instead");
     }
 
-    Block::new()
-        .style(Style::new().bg(CATPPUCCIN.crust))
-        .render(f.area(), f.buffer_mut());
-
-    ui_state.render_kbd(f.buffer_mut());
-
-    let area = f.area();
-
-    // shortcuts window
-    stateful_widgets.shortcuts
-        .iter()
-        .for_each(|w| w.render(area, f.buffer_mut(), &mut ui_state.shortcuts));
+    Ok(())
 }
-
@@ -1,16 +1,12 @@
-use exabind_core::fx::effect::open_all_categories;
-use exabind_core::stateful_widgets::StatefulWidgets;
 use exabind_core::{
     app::ExabindApp,
     event_handler::EventHandler,
     exabind_event::ExabindEvent,
-    fx::effect::starting_up,
-    parser::kde::parse_kglobalshortcuts,
+    parser::exabind::parse_exabind_json,
+    ui::{effects, ui},
     ui_state,
     widget::AnsiKeyboardTklLayout,
 };
-use ratatui::widgets::StatefulWidget;
-use ratatui::Frame;
 use ratzilla::event::KeyCode;
 use ratzilla::ratatui::Terminal;
 use ratzilla::{WebGl2Backend, WebRenderer};
@@ -18,7 +14,6 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::mpsc::Sender;
 use ratzilla::backend::webgl2::{FontAtlasData, WebGl2BackendOptions};
-use tachyonfx::Duration;
 use exabind_core::dispatcher::Dispatcher;
 use exabind_core::key_event::ModifierKeyCode;
 
@@ -28,8 +23,9 @@ fn main() -> std::io::Result<()> {
     // need an event handler for WASM
     let events = EventHandler::new(core::time::Duration::from_millis(33));
     
-    // Bundle KDE shortcuts data at compile time for web
-    let keymap = parse_kglobalshortcuts(include_str!("../../test/kglobalshortcutsrc"));
+    // Bundle KDE shortcuts, in exabind's own format, at compile time for web
+    let keymap = parse_exabind_json(include_str!("../../test/kglobalshortcuts.exabind.json"))
+        .expect("bundled keymap to be valid");
     
     // Create backend with size and set background color
 
@@ -47,16 +43,9 @@ fn main() -> std::io::Result<()> {
     // Initialize keyboard layout and startup effect
     ui_state.screen = terminal.size()?;
     ui_state.reset_kbd_buffer(AnsiKeyboardTklLayout);
-    ui_state.register_kbd_effect(starting_up());
 
-    
     let app = Rc::new(RefCell::new(ExabindApp::new(&mut ui_state, events.sender(), keymap)));
-    {
-        let mut app_ref = app.borrow_mut();
-        let widgets = app_ref.stateful_widgets().category_widgets();
-        let open_categories_fx = open_all_categories(app_ref.sender(), widgets);
-        app_ref.stage_mut().add_effect(open_categories_fx);
-    }
+    app.borrow_mut().start_up(&mut ui_state);
     
     // Set up key event handling
     setup_key_event_handling(&terminal, events.sender());
@@ -107,34 +96,3 @@ fn setup_key_event_handling(
         }
     });
 }
-
-fn effects(
-    elapsed: Duration,
-    app: &mut ExabindApp,
-    f: &mut Frame<'_>,
-) {
-    let area = f.area();
-    app.process_effects(elapsed, f.buffer_mut(), area);
-}
-
-fn ui(
-    f: &mut Frame<'_>,
-    stateful_widgets: &StatefulWidgets,
-    ui_state: &mut ui_state::UiState
-) {
-    ui_state.screen = f.area().as_size();
-    
-    if f.area().is_empty() || f.area().width == 2500 || f.area().height < 3 {
-        return;
-    }
-
-    ui_state.render_kbd(f.buffer_mut());
-
-    let area = f.area();
-
-    // shortcuts window
-    stateful_widgets.shortcuts
-        .iter()
-        .for_each(|w| w.render(area, f.buffer_mut(), &mut ui_state.shortcuts));
-}
-